@@ -1,4 +1,5 @@
 use rand::{Rng, RngCore};
+use rand::seq::index;
 
 use super::individual::*;
 
@@ -60,11 +61,244 @@ impl CrossoverMethod for UniformCrossover {
     }
 }
 
+// Cuts both parents at `k` random points and takes turns at copying
+// the pieces in between, starting with `parent_a`
+pub struct KPointCrossover {
+    k: usize,
+    // where cuts may go; `None` for anywhere
+    boundaries: Option<Vec<usize>>,
+}
+
+impl KPointCrossover {
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0);
+
+        Self { k, boundaries: None }
+    }
+
+    // Cuts only right before the given genes, e.g. at
+    // `Network::neuron_offsets` so that neurons are never split
+    pub fn aligned(mut self, boundaries: Vec<usize>) -> Self {
+        self.boundaries = Some(boundaries);
+        self
+    }
+
+    // sorted and distinct, none at either end of the chromosome
+    fn cuts(&self, rng: &mut dyn RngCore, len: usize) -> Vec<usize> {
+        let mut candidates: Vec<usize> = match &self.boundaries {
+            Some(boundaries) => boundaries
+                .iter()
+                .cloned()
+                .filter(|&cut| cut > 0 && cut < len)
+                .collect(),
+            None => (1..len).collect(),
+        };
+
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let amount = self.k.min(candidates.len());
+
+        let mut cuts: Vec<usize> = index::sample(rng, candidates.len(), amount)
+            .into_iter()
+            .map(|idx| candidates[idx])
+            .collect();
+
+        cuts.sort_unstable();
+        cuts
+    }
+}
+
+impl CrossoverMethod for KPointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let cuts = self.cuts(rng, parent_a.len());
+
+        (0..parent_a.len())
+            .map(|idx| {
+                // how many cuts are behind this gene
+                let crossed = cuts.partition_point(|&cut| cut <= idx);
+
+                if crossed % 2 == 0 {
+                    parent_a[idx]
+                } else {
+                    parent_b[idx]
+                }
+            })
+            .collect()
+    }
+}
+
+// `KPointCrossover` with a single cut
+pub struct SinglePointCrossover(KPointCrossover);
+
+impl SinglePointCrossover {
+    pub fn new() -> Self {
+        Self(KPointCrossover::new(1))
+    }
+
+    // see `KPointCrossover::aligned`
+    pub fn aligned(self, boundaries: Vec<usize>) -> Self {
+        Self(self.0.aligned(boundaries))
+    }
+}
+
+impl Default for SinglePointCrossover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        self.0.crossover(rng, parent_a, parent_b)
+    }
+}
+
+// child = weight * parent_a + (1 - weight) * parent_b, gene by gene
+#[derive(Default)]
+pub struct ArithmeticCrossover {
+    // `None` draws a new weight from 0..=1 for every child
+    weight: Option<f32>,
+}
+
+impl ArithmeticCrossover {
+    pub fn new() -> Self {
+        Self { weight: None }
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        assert!((0.0..=1.0).contains(&weight));
+
+        self.weight = Some(weight);
+        self
+    }
+}
+
+impl CrossoverMethod for ArithmeticCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let weight = self.weight.unwrap_or_else(|| rng.gen_range(0.0..=1.0));
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| weight * a + (1.0 - weight) * b)
+            .collect()
+    }
+}
+
+// BLX-alpha: every gene is drawn uniformly from between the parents'
+// genes, widened on both sides by `alpha` times their distance
+pub struct BlendCrossover {
+    alpha: f32,
+}
+
+impl BlendCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha >= 0.0);
+
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for BlendCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                if a == b {
+                    return a;
+                }
+
+                let spread = self.alpha * (a - b).abs();
+
+                rng.gen_range((a.min(b) - spread)..=(a.max(b) + spread))
+            })
+            .collect()
+    }
+}
+
+// SBX: spreads children around the parents like single-point crossover
+// of binary genes would. The higher the distribution index, the closer
+// children stay to their parents
+pub struct SimulatedBinaryCrossover {
+    eta: f32,
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(eta: f32) -> Self {
+        assert!(eta >= 0.0);
+
+        Self { eta }
+    }
+
+    fn beta(&self, u: f32) -> f32 {
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        if u <= 0.5 {
+            (2.0 * u).powf(exponent)
+        } else {
+            (1.0 / (2.0 * (1.0 - u))).powf(exponent)
+        }
+    }
+}
+
+impl CrossoverMethod for SimulatedBinaryCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let beta = self.beta(rng.gen_range(0.0..1.0));
+
+                // one of the two children SBX makes, at random
+                let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+
+                0.5 * ((a + b) + sign * beta * (a - b))
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
     
     #[test]
     fn uniform_crossover_test() {
@@ -95,4 +329,205 @@ mod tests {
 
         assert_eq!(delta_a, 49);
     }
+
+    fn parents(len: usize) -> (Chromosome, Chromosome) {
+        (
+            Chromosome { genes: (1..=len).map(|n| n as f32).collect() },
+            Chromosome { genes: (1..=len).map(|n| -(n as f32)).collect() },
+        )
+    }
+
+    // how many times the child switches from one parent to the other
+    fn switches(child: &Chromosome) -> usize {
+        child
+            .genes
+            .windows(2)
+            .filter(|pair| pair[0].signum() != pair[1].signum())
+            .count()
+    }
+
+    mod k_point {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(20);
+
+            let child = KPointCrossover::new(3)
+                .crossover(&mut rng, &parent_a, &parent_b);
+
+            assert_eq!(switches(&child), 3);
+            assert_eq!(child[0], parent_a[0]);
+            // every gene stays in place
+            assert!(child
+                .iter()
+                .enumerate()
+                .all(|(idx, &gene)| gene.abs() == parent_a[idx]));
+        }
+
+        #[test]
+        fn more_cuts_than_genes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(4);
+
+            let child = KPointCrossover::new(10)
+                .crossover(&mut rng, &parent_a, &parent_b);
+
+            assert_eq!(child.genes, vec![1.0, -2.0, 3.0, -4.0]);
+        }
+
+        #[test]
+        fn aligned() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(12);
+            let crossover = KPointCrossover::new(2)
+                .aligned(vec![0, 4, 8, 12]);
+
+            for _ in 0..10 {
+                let child = crossover.crossover(&mut rng, &parent_a, &parent_b);
+
+                // both inner boundaries are the only ones to pick
+                assert_eq!(child.genes[..4], parent_a.genes[..4]);
+                assert_eq!(child.genes[4..8], parent_b.genes[4..8]);
+                assert_eq!(child.genes[8..], parent_a.genes[8..]);
+            }
+        }
+    }
+
+    mod single_point {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(20);
+
+            for _ in 0..10 {
+                let child = SinglePointCrossover::new()
+                    .crossover(&mut rng, &parent_a, &parent_b);
+
+                assert_eq!(switches(&child), 1);
+                assert!(child[0] > 0.0 && child[19] < 0.0);
+            }
+        }
+
+        #[test]
+        fn aligned() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents(9);
+            let crossover = SinglePointCrossover::new()
+                .aligned(vec![0, 3, 6]);
+
+            for _ in 0..10 {
+                let child = crossover.crossover(&mut rng, &parent_a, &parent_b);
+                let cut = child.iter().position(|&gene| gene < 0.0).unwrap();
+
+                assert!(cut == 3 || cut == 6);
+            }
+        }
+    }
+
+    mod arithmetic {
+        use super::*;
+
+        #[test]
+        fn with_weight() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a = Chromosome { genes: vec![1.0, 2.0, -4.0] };
+            let parent_b = Chromosome { genes: vec![3.0, 2.0, 0.0] };
+
+            let child = ArithmeticCrossover::new()
+                .with_weight(0.25)
+                .crossover(&mut rng, &parent_a, &parent_b);
+
+            assert_relative_eq!(child.genes.as_slice(), [2.5, 2.0, -1.0].as_ref());
+        }
+
+        #[test]
+        fn random_weight() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a = Chromosome { genes: vec![0.0, 0.0] };
+            let parent_b = Chromosome { genes: vec![1.0, 2.0] };
+
+            let child = ArithmeticCrossover::new()
+                .crossover(&mut rng, &parent_a, &parent_b);
+
+            // a single weight for the whole child
+            assert!(child[0] > 0.0 && child[0] < 1.0);
+            assert_relative_eq!(child[1], 2.0 * child[0]);
+        }
+    }
+
+    mod blend {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a = Chromosome { genes: vec![0.0; 1000] };
+            let parent_b = Chromosome { genes: vec![1.0; 1000] };
+
+            let child = BlendCrossover::new(0.5)
+                .crossover(&mut rng, &parent_a, &parent_b);
+
+            assert!(child.iter().all(|&gene| (-0.5..=1.5).contains(&gene)));
+            // past the parents, on both sides
+            assert!(child.iter().any(|&gene| gene < 0.0));
+            assert!(child.iter().any(|&gene| gene > 1.0));
+        }
+
+        #[test]
+        fn same_genes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent = Chromosome { genes: vec![0.5, -1.0] };
+
+            let child = BlendCrossover::new(0.5)
+                .crossover(&mut rng, &parent, &parent);
+
+            assert_eq!(child, parent);
+        }
+    }
+
+    mod simulated_binary {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a = Chromosome { genes: vec![0.0; 1000] };
+            let parent_b = Chromosome { genes: vec![1.0; 1000] };
+
+            let spread = |eta: f32, rng: &mut ChaCha8Rng| {
+                SimulatedBinaryCrossover::new(eta)
+                    .crossover(rng, &parent_a, &parent_b)
+                    .iter()
+                    .map(|&gene| gene.abs().min((gene - 1.0).abs()))
+                    .sum::<f32>()
+            };
+
+            // children of a higher index stay closer to the parents
+            assert!(spread(20.0, &mut rng) < spread(1.0, &mut rng));
+        }
+
+        #[test]
+        fn beta() {
+            let sbx = SimulatedBinaryCrossover::new(1.0);
+
+            assert_relative_eq!(sbx.beta(0.5), 1.0);
+            assert_relative_eq!(sbx.beta(0.125), 0.5);
+            assert_relative_eq!(sbx.beta(0.875), 2.0);
+        }
+
+        #[test]
+        fn same_genes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent = Chromosome { genes: vec![0.5, -1.0] };
+
+            let child = SimulatedBinaryCrossover::new(2.0)
+                .crossover(&mut rng, &parent, &parent);
+
+            assert_eq!(child, parent);
+        }
+    }
 }
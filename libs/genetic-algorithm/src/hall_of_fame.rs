@@ -0,0 +1,175 @@
+use super::individual::*;
+
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub chromosome: Chromosome,
+    pub fitness: f32,
+    // generation the individual was seen in with this fitness
+    pub generation: usize,
+}
+
+// The best individuals seen across all generations, best first.
+//
+// Individuals that come back unchanged (e.g. elites) take a single
+// entry, holding their best fitness
+#[derive(Clone, Debug)]
+pub struct HallOfFame {
+    capacity: usize,
+    entries: Vec<Entry>,
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        Self {
+            capacity,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn record<I>(&mut self, population: &[I], generation: usize)
+    where
+        I: Individual,
+    {
+        for individual in population {
+            let fitness = individual.fitness();
+
+            if fitness.is_nan() {
+                continue;
+            }
+
+            let chromosome = individual.as_chromosome();
+
+            if let Some(idx) = self.entries
+                .iter()
+                .position(|entry| entry.chromosome.genes == chromosome.genes)
+            {
+                if self.entries[idx].fitness >= fitness {
+                    continue;
+                }
+
+                self.entries.remove(idx);
+            } else if self.entries.len() == self.capacity
+                && self.entries[self.capacity - 1].fitness >= fitness
+            {
+                continue;
+            }
+
+            // after entries that are at least as good, so that
+            // the earlier of two equals stays ahead
+            let idx = self.entries
+                .iter()
+                .position(|entry| entry.fitness < fitness)
+                .unwrap_or(self.entries.len());
+
+            self.entries.insert(idx, Entry {
+                chromosome: chromosome.clone(),
+                fitness,
+                generation,
+            });
+
+            self.entries.truncate(self.capacity);
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn best(&self) -> Option<&Entry> {
+        self.entries.first()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    struct TestIndividual {
+        fitness: f32,
+        chromosome: Chromosome,
+    }
+
+    impl Individual for TestIndividual {
+        fn fitness(&self) -> f32 {
+            self.fitness
+        }
+
+        fn as_chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+
+        fn from_chromosome(_chromosome: Chromosome) -> Self {
+            panic!("Not implemented for TestIndividual")
+        }
+    }
+
+    fn individual(gene: f32, fitness: f32) -> TestIndividual {
+        TestIndividual {
+            fitness,
+            chromosome: Chromosome { genes: vec![gene] },
+        }
+    }
+
+    fn summary(hall_of_fame: &HallOfFame) -> Vec<(f32, f32, usize)> {
+        hall_of_fame
+            .entries()
+            .iter()
+            .map(|entry| (entry.chromosome[0], entry.fitness, entry.generation))
+            .collect()
+    }
+
+    mod record {
+        use super::*;
+
+        #[test]
+        fn keeps_the_best() {
+            let mut hall_of_fame = HallOfFame::new(3);
+
+            hall_of_fame.record(&[individual(1.0, 1.0), individual(2.0, 5.0)], 0);
+            hall_of_fame.record(&[individual(3.0, 3.0), individual(4.0, 0.5), individual(5.0, 4.0)], 1);
+
+            assert_eq!(
+                summary(&hall_of_fame),
+                vec![(2.0, 5.0, 0), (5.0, 4.0, 1), (3.0, 3.0, 1)]
+            );
+            assert_eq!(hall_of_fame.best().unwrap().fitness, 5.0);
+        }
+
+        #[test]
+        fn earlier_wins_ties() {
+            let mut hall_of_fame = HallOfFame::new(2);
+
+            hall_of_fame.record(&[individual(1.0, 2.0)], 0);
+            hall_of_fame.record(&[individual(2.0, 2.0), individual(3.0, 2.0)], 1);
+
+            assert_eq!(summary(&hall_of_fame), vec![(1.0, 2.0, 0), (2.0, 2.0, 1)]);
+        }
+
+        #[test]
+        fn merges_returning_individuals() {
+            let mut hall_of_fame = HallOfFame::new(3);
+
+            hall_of_fame.record(&[individual(1.0, 2.0), individual(2.0, 1.0)], 0);
+            hall_of_fame.record(&[individual(1.0, 1.0)], 1);
+            hall_of_fame.record(&[individual(2.0, 3.0)], 2);
+
+            assert_eq!(summary(&hall_of_fame), vec![(2.0, 3.0, 2), (1.0, 2.0, 0)]);
+        }
+
+        #[test]
+        fn skips_nan() {
+            let mut hall_of_fame = HallOfFame::new(2);
+
+            hall_of_fame.record(&[individual(1.0, f32::NAN)], 0);
+
+            assert!(hall_of_fame.best().is_none());
+        }
+    }
+}
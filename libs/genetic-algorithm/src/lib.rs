@@ -1,15 +1,24 @@
 // #![feature(type_alias_impl_trait)]
-use rand::RngCore;
+use rand::{Rng, RngCore};
 
 pub mod individual;
 pub mod selection;
 pub mod crossover;
 pub mod mutation;
+pub mod hall_of_fame;
 
 pub struct GeneticAlgorithm<S, C> {
     selection_method: S,
     crossover_method: C,
-    mutation_method: Box<dyn mutation::MutationMethod>
+    mutation_method: Box<dyn mutation::MutationMethod>,
+    // how many of the fittest individuals are carried over as they are
+    elites: usize,
+    // probability of a child being bred by crossover rather than
+    // cloned from its first parent
+    crossover_rate: f32,
+    // probability of a child going through mutation at all, on top of
+    // the per-gene chance of the mutation method
+    mutation_rate: f32,
 }
 
 impl<S, C> GeneticAlgorithm<S, C>
@@ -25,10 +34,32 @@ where
         Self {
             selection_method,
             crossover_method,
-            mutation_method: Box::new(mutation_method)
+            mutation_method: Box::new(mutation_method),
+            elites: 0,
+            crossover_rate: 1.0,
+            mutation_rate: 1.0,
         }
     }
 
+    pub fn with_elites(mut self, elites: usize) -> Self {
+        self.elites = elites;
+        self
+    }
+
+    pub fn with_crossover_rate(mut self, rate: f32) -> Self {
+        assert!((0.0..=1.0).contains(&rate));
+
+        self.crossover_rate = rate;
+        self
+    }
+
+    pub fn with_mutation_rate(mut self, rate: f32) -> Self {
+        assert!((0.0..=1.0).contains(&rate));
+
+        self.mutation_rate = rate;
+        self
+    }
+
     pub fn iterate<I>(
         &self,
         rng: &mut dyn RngCore,
@@ -36,31 +67,74 @@ where
     where
         I: individual::Individual,
     {
-        (0..population.len())
+        let elites = self.elites.min(population.len());
+
+        // Step #0: the fittest survive untouched, best first
+        let mut survivors = Vec::with_capacity(population.len());
+
+        if elites > 0 {
+            let mut ranked: Vec<&I> = population.iter().collect();
+            let best_first = |a: &&I, b: &&I| selection::compare_fitness(*b, *a);
+
+            // no need to sort everyone else
+            ranked.select_nth_unstable_by(elites - 1, best_first);
+            ranked[..elites].sort_by(best_first);
+
+            survivors.extend(
+                ranked[..elites]
+                    .iter()
+                    .map(|individual| I::from_chromosome(individual.as_chromosome().clone()))
+            );
+        }
+
+        let offspring = population.len() - elites;
+
+        // batched methods pick every parent up front, two per child
+        let batch = if self.selection_method.batched() {
+            self.selection_method
+                .select_many(rng, population, 2 * offspring)
+        } else {
+            Vec::new()
+        };
+
+        let mut batch = batch.chunks_exact(2);
+
+        let children = (0..offspring)
             .map(|_| {
                 // Step #1: parent selection
-                let parent_a = self
-                    .selection_method
-                    .select(rng, population)
-                    .as_chromosome();
-                let parent_b = self
-                    .selection_method
-                    .select(rng, population)
-                    .as_chromosome();
-
-                // Step #2: crossover/mix "traits"
-                let mut child = self
-                    .crossover_method
-                    .crossover(rng, parent_a, parent_b);
+                let (parent_a, parent_b) = match batch.next() {
+                    Some(parents) => (parents[0], parents[1]),
+                    None => (
+                        self.selection_method.select(rng, population),
+                        self.selection_method.select(rng, population),
+                    ),
+                };
+
+                let parent_a = parent_a.as_chromosome();
+                let parent_b = parent_b.as_chromosome();
+
+                // Step #2: crossover/mix "traits", or clone
+                // (rates of 1.0 don't touch the rng)
+                let mut child = if self.crossover_rate >= 1.0 || rng.gen_bool(self.crossover_rate as _) {
+                    self
+                        .crossover_method
+                        .crossover(rng, parent_a, parent_b)
+                } else {
+                    parent_a.clone()
+                };
 
                 // Step #3: mutation
-                self
-                    .mutation_method
-                    .mutate(rng, &mut child);
+                if self.mutation_rate >= 1.0 || rng.gen_bool(self.mutation_rate as _) {
+                    self
+                        .mutation_method
+                        .mutate(rng, &mut child);
+                }
 
                 I::from_chromosome(child)
-            })
-            .collect()
+            });
+
+        survivors.extend(children);
+        survivors
     }
 }
 
@@ -137,7 +211,7 @@ mod tests {
             let genetic_algo = GeneticAlgorithm::new(
                 selection::RoulleteWheelSelection::new(),
                 crossover::UniformCrossover::new(),
-                mutation::GaussianMutation::new(0.5, 0.5)
+                mutation::UniformMutation::new(0.5, 0.5)
             );
 
             let mut population = vec![
@@ -166,5 +240,135 @@ mod tests {
                 expected_population
             );
         }
+
+        #[test]
+        fn batched_selection() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let genetic_algo = GeneticAlgorithm::new(
+                selection::StochasticUniversalSampling::new(),
+                crossover::UniformCrossover::new(),
+                mutation::GaussianMutation::new(0.0, 0.0)
+            );
+
+            // only the fittest has any share of the wheel
+            let population = vec![
+                create_individual(&[0.0, 0.0]),
+                create_individual(&[1.0, 1.0]),
+                create_individual(&[0.0, 0.0]),
+            ];
+
+            let next = genetic_algo
+                .iterate(&mut rng, &population);
+
+            assert_eq!(next, vec![population[1].clone(); 3]);
+        }
+
+        #[test]
+        fn elites() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let genetic_algo = GeneticAlgorithm::new(
+                selection::RoulleteWheelSelection::new(),
+                crossover::UniformCrossover::new(),
+                mutation::GaussianMutation::new(1.0, 1.0)
+            )
+                .with_elites(2);
+
+            let population = vec![
+                create_individual(&[0.1, 0.0, 0.0, 0.0]),
+                create_individual(&[0.7, 2.1, -0.5, 3.3]),
+                create_individual(&[1.3, 1.7, 2.4, 0.0]),
+                create_individual(&[-0.1, 0.0, 2.1, 1.1]),
+            ];
+
+            let next = genetic_algo
+                .iterate(&mut rng, &population);
+
+            assert_eq!(next.len(), population.len());
+            assert_eq!(next[0], population[1]);
+            assert_eq!(next[1], population[2]);
+            assert!(next[2..]
+                .iter()
+                .all(|child| !population.contains(child)));
+        }
+
+        #[test]
+        fn elites_past_population() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let genetic_algo = GeneticAlgorithm::new(
+                selection::RoulleteWheelSelection::new(),
+                crossover::UniformCrossover::new(),
+                mutation::GaussianMutation::new(1.0, 1.0)
+            )
+                .with_elites(5);
+
+            let population = vec![
+                create_individual(&[0.5, 0.0]),
+                create_individual(&[1.0, 1.0]),
+            ];
+
+            let next = genetic_algo
+                .iterate(&mut rng, &population);
+
+            assert_eq!(next, vec![population[1].clone(), population[0].clone()]);
+        }
+
+        #[test]
+        fn clones() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let genetic_algo = GeneticAlgorithm::new(
+                selection::RoulleteWheelSelection::new(),
+                crossover::UniformCrossover::new(),
+                mutation::GaussianMutation::new(1.0, 1.0)
+            )
+                .with_crossover_rate(0.0)
+                .with_mutation_rate(0.0);
+
+            let population = vec![
+                create_individual(&[0.1, 0.0, 0.0, 0.0]),
+                create_individual(&[0.7, 2.1, -0.5, 3.3]),
+                create_individual(&[1.3, 1.7, 2.4, 0.0]),
+                create_individual(&[-0.1, 0.0, 2.1, 1.1]),
+            ];
+
+            let next = genetic_algo
+                .iterate(&mut rng, &population);
+
+            assert_eq!(next.len(), population.len());
+            assert!(next
+                .iter()
+                .all(|child| population.contains(child)));
+        }
+
+        #[test]
+        fn mutation_rate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let genetic_algo = GeneticAlgorithm::new(
+                selection::RoulleteWheelSelection::new(),
+                crossover::UniformCrossover::new(),
+                mutation::GaussianMutation::new(1.0, 1.0)
+            )
+                .with_crossover_rate(0.0)
+                .with_mutation_rate(0.5);
+
+            let population: Vec<_> = (1..=100)
+                .map(|n| create_individual(&[n as f32, 1.0]))
+                .collect();
+
+            let next = genetic_algo
+                .iterate(&mut rng, &population);
+
+            // only the unmutated clones are left as they were
+            let clones = next
+                .iter()
+                .filter(|child| population.contains(child))
+                .count();
+
+            assert!(clones > 30 && clones < 70, "{} clones", clones);
+        }
     }
 }
@@ -1,4 +1,5 @@
 use rand::{Rng,RngCore};
+use rand_distr::{Distribution, Normal};
 
 use super::individual::*;
 
@@ -10,32 +11,130 @@ pub trait MutationMethod {
     );
 }
 
+// Keeps mutated genes within `min..=max`
+#[derive(Clone, Copy, Debug)]
+pub enum Bounds {
+    // genes past a bound stop at it
+    Clamp { min: f32, max: f32 },
+    // genes past a bound bounce back off it by the overshoot
+    Reflect { min: f32, max: f32 },
+}
+
+impl Bounds {
+    fn apply(&self, gene: f32) -> f32 {
+        match *self {
+            Self::Clamp { min, max } => gene.clamp(min, max),
+            Self::Reflect { min, max } => {
+                let range = max - min;
+
+                if range == 0.0 {
+                    return min;
+                }
+
+                // one period is there and back again,
+                // which handles overshooting by more than `range`
+                let offset = (gene - min).rem_euclid(2.0 * range);
+
+                if offset > range {
+                    max - (offset - range)
+                } else {
+                    min + offset
+                }
+            }
+        }
+    }
+
+    fn assert_valid(&self) {
+        let (Self::Clamp { min, max } | Self::Reflect { min, max }) = *self;
+
+        assert!(min <= max);
+    }
+}
+
 pub struct GaussianMutation {
+    //* probability of changing n genes
+    //* if n = 0.0, no gene is slated for mutation
+    //* if n = 1.0, all genes are slated for mutation
+    chance: f32,
+    //* standard deviation of the change
+    //* if n = 0.0, no mutation
+    sigma: f32,
+    bounds: Option<Bounds>,
+}
+
+impl GaussianMutation {
+    pub fn new(chance: f32, sigma: f32) -> Self {
+        assert!(chance >= 0.0 && sigma >= 0.0);
+
+        Self { chance, sigma, bounds: None }
+    }
+
+    pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+        bounds.assert_valid();
+
+        self.bounds = Some(bounds);
+        self
+    }
+}
+
+impl MutationMethod for GaussianMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let normal = Normal::new(0.0, self.sigma)
+            .unwrap();
+
+        for gene in child.mut_iter() {
+            if rng.gen_bool(self.chance as _) {
+                *gene += normal.sample(rng);
+
+                if let Some(bounds) = self.bounds {
+                    *gene = bounds.apply(*gene);
+                }
+            }
+        }
+    }
+}
+
+// Nudges genes by uniform noise in +-`coeff`; this is what
+// `GaussianMutation` used to do, kept so that older experiments
+// can be reproduced
+pub struct UniformMutation {
     //* probability of changing n genes
     //* if n = 0.0, no gene is slated for mutation
     //* if n = 1.0, all genes are slated for mutation
     chance: f32,
     //* Magnitude of the change
     //* if n = 0.0, no mutation
-    //* if n = 1.0, mutation upto +-3.0
+    //* if n = 1.0, mutation upto +-1.0
     coeff: f32,
+    bounds: Option<Bounds>,
 }
 
-impl GaussianMutation {
+impl UniformMutation {
     pub fn new(chance: f32, coeff: f32) -> Self {
         assert!(chance >= 0.0 && coeff >= 0.0);
 
-        Self { chance, coeff }
+        Self { chance, coeff, bounds: None }
+    }
+
+    pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+        bounds.assert_valid();
+
+        self.bounds = Some(bounds);
+        self
     }
 }
 
-impl MutationMethod for GaussianMutation {
+impl MutationMethod for UniformMutation {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
         for gene in child.mut_iter() {
             let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
 
             if rng.gen_bool(self.chance as _) {
                 *gene += sign * self.coeff * rng.gen::<f32>();
+
+                if let Some(bounds) = self.bounds {
+                    *gene = bounds.apply(*gene);
+                }
             }
         }
     }
@@ -176,4 +275,101 @@ mod tests {
             }
         }
     }
+
+    mod gaussian {
+        use super::*;
+
+        #[test]
+        fn is_normally_distributed() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = Chromosome {
+                genes: vec![0.0; 10_000]
+            };
+
+            GaussianMutation::new(1.0, 0.5)
+                .mutate(&mut rng, &mut child);
+
+            let mean = child.iter().sum::<f32>() / child.len() as f32;
+            let std_dev = (child.iter().map(|gene| (gene - mean).powi(2)).sum::<f32>()
+                / child.len() as f32)
+                .sqrt();
+
+            assert_relative_eq!(mean, 0.0, epsilon = 0.02);
+            assert_relative_eq!(std_dev, 0.5, epsilon = 0.02);
+            // uniform noise would never leave +-sigma that far
+            assert!(child.iter().any(|gene| gene.abs() > 1.0));
+        }
+
+        #[test]
+        fn with_bounds() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = Chromosome {
+                genes: vec![0.9; 1000]
+            };
+
+            GaussianMutation::new(1.0, 1.0)
+                .with_bounds(Bounds::Clamp { min: -1.0, max: 1.0 })
+                .mutate(&mut rng, &mut child);
+
+            assert!(child.iter().all(|gene| (-1.0..=1.0).contains(gene)));
+            assert!(child.iter().any(|&gene| gene == 1.0));
+        }
+    }
+
+    mod uniform {
+        use super::*;
+
+        #[test]
+        fn stays_within_coeff() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = Chromosome {
+                genes: vec![0.0; 1000]
+            };
+
+            UniformMutation::new(1.0, 0.3)
+                .mutate(&mut rng, &mut child);
+
+            assert!(child.iter().all(|gene| gene.abs() <= 0.3));
+            assert!(child.iter().any(|gene| gene.abs() > 0.25));
+        }
+
+        #[test]
+        fn with_bounds() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = Chromosome {
+                genes: vec![0.0; 1000]
+            };
+
+            UniformMutation::new(1.0, 1.0)
+                .with_bounds(Bounds::Reflect { min: -0.5, max: 0.5 })
+                .mutate(&mut rng, &mut child);
+
+            assert!(child.iter().all(|gene| (-0.5..=0.5).contains(gene)));
+        }
+    }
+
+    mod bounds {
+        use super::*;
+
+        #[test]
+        fn clamp() {
+            let bounds = Bounds::Clamp { min: -1.0, max: 2.0 };
+
+            assert_eq!(bounds.apply(0.5), 0.5);
+            assert_eq!(bounds.apply(2.5), 2.0);
+            assert_eq!(bounds.apply(-3.0), -1.0);
+        }
+
+        #[test]
+        fn reflect() {
+            let bounds = Bounds::Reflect { min: -1.0, max: 2.0 };
+
+            assert_relative_eq!(bounds.apply(0.5), 0.5);
+            assert_relative_eq!(bounds.apply(2.5), 1.5);
+            assert_relative_eq!(bounds.apply(-1.5), -0.5);
+            // past the opposite bound too
+            assert_relative_eq!(bounds.apply(5.5), -0.5);
+            assert_relative_eq!(bounds.apply(-5.0), 1.0);
+        }
+    }
 }
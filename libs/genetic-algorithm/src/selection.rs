@@ -1,4 +1,7 @@
-use rand::RngCore;
+use std::cmp::Ordering;
+
+use rand::{Rng, RngCore};
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::seq::SliceRandom;
 
 use super::individual::*;
@@ -11,6 +14,27 @@ pub trait SelectionMethod {
     ) -> &'a I
     where
         I: Individual;
+
+    // whether `GeneticAlgorithm::iterate` should pick all parents of
+    // a generation with one `select_many` rather than one by one
+    fn batched(&self) -> bool {
+        false
+    }
+
+    // `count` parents at once, for methods that pick them together
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        (0..count)
+            .map(|_| self.select(rng, population))
+            .collect()
+    }
 }
 
 pub struct RoulleteWheelSelection;
@@ -38,6 +62,265 @@ impl SelectionMethod for RoulleteWheelSelection {
     }
 }
 
+// Picks `size` random individuals (with replacement) and ranks them by
+// fitness; the best one wins with `probability`, the runner-up with
+// `probability * (1 - probability)`, and so on, the last one taking
+// whatever's left.
+//
+// Only the order of fitnesses matters, so zero and negative values are
+// fine; bigger tournaments and higher probabilities mean more pressure.
+// Individuals with a NaN fitness are left out, unless that's everyone
+pub struct TournamentSelection {
+    size: usize,
+    probability: f32,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize, probability: f32) -> Self {
+        assert!(size > 0);
+        assert!((0.0..=1.0).contains(&probability));
+
+        Self { size, probability }
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I]
+    ) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        // NaNs only get to take part when there's no one else
+        let mut entrants: Vec<&I> = population
+            .iter()
+            .filter(|individual| !individual.fitness().is_nan())
+            .collect();
+
+        if entrants.is_empty() {
+            entrants = population.iter().collect();
+        }
+
+        let mut contestants: Vec<&I> = (0..self.size)
+            .map(|_| *entrants.choose(rng).unwrap())
+            .collect();
+
+        // fittest first; NaNs lose against everything
+        contestants.sort_by(|a, b| {
+            let (a, b) = (a.fitness(), b.fitness());
+
+            match (a.is_nan(), b.is_nan()) {
+                (false, false) => b.partial_cmp(&a).unwrap(),
+                (a, b) => a.cmp(&b),
+            }
+        });
+
+        let last = contestants.len() - 1;
+
+        for contestant in &contestants[..last] {
+            if rng.gen_bool(self.probability as _) {
+                return contestant;
+            }
+        }
+
+        contestants[last]
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Ranking {
+    // the best individual is `pressure` times as likely to be picked
+    // as an average one, the worst one `2 - pressure` times; 1..=2
+    Linear { pressure: f32 },
+    // every rank is `base` times as likely to be picked as the one
+    // above it; 0..1, lower means more pressure
+    Exponential { base: f32 },
+}
+
+// Like `RoulleteWheelSelection`, but weighted by the rank of the fitness
+// rather than the fitness itself - so only the order matters and one
+// outstanding individual can't take over the population
+pub struct RankSelection {
+    ranking: Ranking,
+}
+
+impl RankSelection {
+    pub fn linear(pressure: f32) -> Self {
+        assert!((1.0..=2.0).contains(&pressure));
+
+        Self { ranking: Ranking::Linear { pressure } }
+    }
+
+    pub fn exponential(base: f32) -> Self {
+        assert!(base > 0.0 && base < 1.0);
+
+        Self { ranking: Ranking::Exponential { base } }
+    }
+
+    // selection weight of the individual at `rank`, 0 being the worst
+    fn weight(&self, rank: usize, population: usize) -> f32 {
+        match self.ranking {
+            Ranking::Linear { pressure } => {
+                if population == 1 {
+                    return 1.0;
+                }
+
+                (2.0 - pressure)
+                    + 2.0 * (pressure - 1.0) * rank as f32 / (population - 1) as f32
+            }
+            Ranking::Exponential { base } => base.powi((population - 1 - rank) as i32),
+        }
+    }
+}
+
+impl SelectionMethod for RankSelection {
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I]
+    ) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    // ranks only once for all the parents
+    fn batched(&self) -> bool {
+        true
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        let ranked = by_fitness(population);
+
+        let ranks = WeightedIndex::new(
+            (0..ranked.len()).map(|rank| self.weight(rank, ranked.len()))
+        )
+            .expect("population cannot be zero");
+
+        (0..count)
+            .map(|_| ranked[ranks.sample(rng)])
+            .collect()
+    }
+}
+
+// Fitness-proportionate like `RoulleteWheelSelection`, but all parents
+// are picked with a single spin: evenly spaced pointers over the wheel,
+// so that every individual gets within one of its expected number of
+// picks. Fitness must not be negative; when it's all zero, everyone
+// gets an equal share.
+//
+// `select` is a spin with one pointer, i.e. a plain roulette wheel
+#[derive(Default)]
+pub struct StochasticUniversalSampling;
+
+impl StochasticUniversalSampling {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SelectionMethod for StochasticUniversalSampling {
+    fn select<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I]
+    ) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn batched(&self) -> bool {
+        true
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+        assert!(population.iter().all(|individual| individual.fitness() >= 0.0));
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let total: f32 = population
+            .iter()
+            .map(|individual| individual.fitness())
+            .sum();
+
+        let share = |individual: &I| {
+            if total > 0.0 { individual.fitness() } else { 1.0 }
+        };
+
+        let total = if total > 0.0 { total } else { population.len() as f32 };
+        let spacing = total / count as f32;
+        let mut pointer = rng.gen_range(0.0..spacing);
+
+        let mut selected = Vec::with_capacity(count);
+        let mut reached = 0.0;
+
+        for individual in population {
+            reached += share(individual);
+
+            while pointer < reached && selected.len() < count {
+                selected.push(individual);
+                pointer += spacing;
+            }
+        }
+
+        // rounding can leave the last pointer(s) just past the end
+        while selected.len() < count {
+            selected.push(&population[population.len() - 1]);
+        }
+
+        // pointers sweep the population in order; callers pair up
+        // consecutive parents
+        selected.shuffle(rng);
+        selected
+    }
+}
+
+// worst first; NaNs count as the worst
+fn by_fitness<I: Individual>(population: &[I]) -> Vec<&I> {
+    let mut ranked: Vec<&I> = population.iter().collect();
+
+    ranked.sort_by(|a, b| compare_fitness(*a, *b));
+    ranked
+}
+
+// `Less` for the worse of the two; NaNs count as the worst
+pub(crate) fn compare_fitness<I: Individual>(a: &I, b: &I) -> Ordering {
+    let (a, b) = (a.fitness(), b.fitness());
+
+    match (a.is_nan(), b.is_nan()) {
+        (false, false) => a.partial_cmp(&b).unwrap(),
+        (a, b) => b.cmp(&a),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,5 +393,234 @@ mod tests {
 
             assert_eq!(actual_histogram, expected_histogram);
         }
+
+        fn histogram<S: SelectionMethod>(method: S, population: &[TestIndividual]) -> BTreeMap<i32, usize> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            (0..1000)
+                .map(|_| method.select(&mut rng, population))
+                .fold(BTreeMap::default(), |mut histogram, individual| {
+                    *histogram
+                        .entry(individual.fitness() as i32)
+                        .or_default() += 1;
+
+                    histogram
+                })
+        }
+
+        #[test]
+        fn linear_rank() {
+            let population = vec![
+                TestIndividual::new(5.0),
+                TestIndividual::new(1.0),
+                TestIndividual::new(100.0),
+                TestIndividual::new(3.0)
+            ];
+
+            let actual_histogram = histogram(RankSelection::linear(2.0), &population);
+
+            // weights 0, 2/3, 4/3, 2 => 0, 1/6, 2/6, 3/6
+            let expected_histogram = btreemap!{
+                3 => 164,
+                5 => 337,
+                100 => 499
+            };
+
+            assert_eq!(actual_histogram, expected_histogram);
+        }
+
+        #[test]
+        fn linear_rank_select_many() {
+            let population = vec![
+                TestIndividual::new(5.0),
+                TestIndividual::new(1.0),
+                TestIndividual::new(100.0),
+                TestIndividual::new(3.0)
+            ];
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let actual_histogram = RankSelection::linear(2.0)
+                .select_many(&mut rng, &population, 1000)
+                .into_iter()
+                .fold(BTreeMap::default(), |mut histogram, individual| {
+                    *histogram
+                        .entry(individual.fitness() as i32)
+                        .or_default() += 1;
+
+                    histogram
+                });
+
+            // same draws as a thousand `select`s
+            assert_eq!(actual_histogram, histogram(RankSelection::linear(2.0), &population));
+        }
+
+        #[test]
+        fn linear_rank_without_pressure_is_uniform() {
+            let method = RankSelection::linear(1.0);
+
+            assert_eq!(method.weight(0, 4), 1.0);
+            assert_eq!(method.weight(3, 4), 1.0);
+        }
+
+        #[test]
+        fn exponential_rank() {
+            let population = vec![
+                TestIndividual::new(-1.0),
+                TestIndividual::new(0.0),
+                TestIndividual::new(2.0)
+            ];
+
+            let actual_histogram = histogram(RankSelection::exponential(0.5), &population);
+
+            // weights 1/4, 1/2, 1 => 1/7, 2/7, 4/7
+            let expected_histogram = btreemap!{
+                -1 => 145,
+                0 => 292,
+                2 => 563
+            };
+
+            assert_eq!(actual_histogram, expected_histogram);
+        }
+
+        #[test]
+        fn sus() {
+            let population = vec![
+                TestIndividual::new(1.0),
+                TestIndividual::new(2.0),
+                TestIndividual::new(3.0),
+                TestIndividual::new(4.0)
+            ];
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let method = StochasticUniversalSampling::new();
+
+            for _ in 0..100 {
+                let mut counts = BTreeMap::<i32, usize>::new();
+
+                for individual in method.select_many(&mut rng, &population, 10) {
+                    *counts.entry(individual.fitness() as i32).or_default() += 1;
+                }
+
+                // exactly the expected number of picks
+                assert_eq!(counts, btreemap!{ 1 => 1, 2 => 2, 3 => 3, 4 => 4 });
+            }
+        }
+
+        #[test]
+        fn sus_spread() {
+            let population = vec![
+                TestIndividual::new(1.0),
+                TestIndividual::new(1.5),
+                TestIndividual::new(0.5)
+            ];
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let method = StochasticUniversalSampling::new();
+
+            for _ in 0..100 {
+                let selected = method.select_many(&mut rng, &population, 4);
+                let count = |fitness: f32| selected
+                    .iter()
+                    .filter(|individual| individual.fitness() == fitness)
+                    .count();
+
+                // expected: 4/3, 2 and 2/3 picks
+                assert_eq!(selected.len(), 4);
+                assert!((1..=2).contains(&count(1.0)));
+                assert_eq!(count(1.5), 2);
+                assert!((0..=1).contains(&count(0.5)));
+            }
+        }
+
+        #[test]
+        fn sus_with_zero_fitness() {
+            let population = vec![
+                TestIndividual::new(0.0),
+                TestIndividual::new(0.0)
+            ];
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let selected = StochasticUniversalSampling::new()
+                .select_many(&mut rng, &population, 6);
+
+            assert_eq!(selected.len(), 6);
+        }
+
+        #[test]
+        fn tournament() {
+            let population = vec![
+                TestIndividual::new(1.0),
+                TestIndividual::new(2.0),
+                TestIndividual::new(3.0),
+                TestIndividual::new(5.0)
+            ];
+
+            let actual_histogram = histogram(TournamentSelection::new(2, 1.0), &population);
+
+            // the best of two random picks: (2k - 1) / 16 for the k-th worst
+            let expected_histogram = btreemap!{
+                1 => 56,
+                2 => 189,
+                3 => 343,
+                5 => 412
+            };
+
+            assert_eq!(actual_histogram, expected_histogram);
+        }
+
+        #[test]
+        fn tournament_with_zero_and_negative_fitness() {
+            let population = vec![
+                TestIndividual::new(0.0),
+                TestIndividual::new(0.0),
+                TestIndividual::new(-2.0),
+            ];
+
+            let actual_histogram = histogram(TournamentSelection::new(3, 0.75), &population);
+
+            assert_eq!(actual_histogram.len(), 2);
+            assert!(actual_histogram[&0] > actual_histogram[&-2]);
+        }
+
+        #[test]
+        fn tournament_of_one_is_uniform() {
+            let population = vec![
+                TestIndividual::new(1.0),
+                TestIndividual::new(100.0),
+            ];
+
+            let actual_histogram = histogram(TournamentSelection::new(1, 1.0), &population);
+
+            assert!(actual_histogram[&1] > 450);
+            assert!(actual_histogram[&100] > 450);
+        }
+
+        #[test]
+        fn tournament_ignores_nan() {
+            let population = vec![
+                TestIndividual::new(f32::NAN),
+                TestIndividual::new(-1.0),
+            ];
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let method = TournamentSelection::new(8, 1.0);
+
+            for _ in 0..100 {
+                assert_eq!(method.select(&mut rng, &population).fitness(), -1.0);
+            }
+        }
+
+        #[test]
+        fn tournament_of_nan() {
+            let population = vec![TestIndividual::new(f32::NAN)];
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            assert!(TournamentSelection::new(2, 0.5)
+                .select(&mut rng, &population)
+                .fitness()
+                .is_nan());
+        }
     }
 }
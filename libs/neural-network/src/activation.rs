@@ -0,0 +1,198 @@
+use super::float::Float;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Activation {
+    #[default]
+    ReLU,
+    // slope used for negative inputs
+    LeakyReLU(f32),
+    Sigmoid,
+    Tanh,
+    Identity,
+    Softsign,
+    Step,
+    Custom(fn(f32) -> f32),
+}
+
+impl Activation {
+    pub fn apply(&self, x: f32) -> f32 {
+        self.eval(x)
+    }
+
+    // d(apply)/dx, evaluated at the pre-activation `x`
+    pub fn derivative(&self, x: f32) -> f32 {
+        self.eval_derivative(x)
+    }
+
+    // `Custom` functions only work on `f32`, so they round other scalars
+    pub(crate) fn eval<F: Float>(&self, x: F) -> F {
+        let zero = F::zero();
+        let one = F::one();
+
+        match *self {
+            Self::ReLU => x.max(zero),
+            Self::LeakyReLU(slope) => {
+                if x > zero {
+                    x
+                } else {
+                    F::from_f32(slope) * x
+                }
+            }
+            Self::Sigmoid => one / (one + (-x).exp()),
+            Self::Tanh => x.tanh(),
+            Self::Identity => x,
+            Self::Softsign => x / (one + x.abs()),
+            Self::Step => {
+                if x > zero {
+                    one
+                } else {
+                    zero
+                }
+            }
+            Self::Custom(f) => F::from_f32(f(x.to_f32())),
+        }
+    }
+
+    pub(crate) fn eval_derivative<F: Float>(&self, x: F) -> F {
+        let zero = F::zero();
+        let one = F::one();
+
+        match *self {
+            Self::ReLU => {
+                if x > zero {
+                    one
+                } else {
+                    zero
+                }
+            }
+            Self::LeakyReLU(slope) => {
+                if x > zero {
+                    one
+                } else {
+                    F::from_f32(slope)
+                }
+            }
+            Self::Sigmoid => {
+                let y = self.eval(x);
+                y * (one - y)
+            }
+            Self::Tanh => one - x.tanh().powi(2),
+            Self::Identity => one,
+            Self::Softsign => one / (one + x.abs()).powi(2),
+            // flat everywhere but at 0, so there's nothing to follow
+            Self::Step => zero,
+            // central difference, since we only have the function itself
+            Self::Custom(f) => {
+                const H: f32 = 1e-3;
+
+                let x = x.to_f32();
+
+                F::from_f32((f(x + H) - f(x - H)) / (2.0 * H))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    mod apply {
+        use super::*;
+
+        #[test]
+        fn relu() {
+            assert_relative_eq!(Activation::ReLU.apply(-0.5), 0.0);
+            assert_relative_eq!(Activation::ReLU.apply(0.5), 0.5);
+        }
+
+        #[test]
+        fn leaky_relu() {
+            assert_relative_eq!(Activation::LeakyReLU(0.1).apply(-0.5), -0.05);
+            assert_relative_eq!(Activation::LeakyReLU(0.1).apply(0.5), 0.5);
+        }
+
+        #[test]
+        fn sigmoid() {
+            assert_relative_eq!(Activation::Sigmoid.apply(0.0), 0.5);
+            assert_relative_eq!(Activation::Sigmoid.apply(2.0), 0.8807971);
+        }
+
+        #[test]
+        fn tanh() {
+            assert_relative_eq!(Activation::Tanh.apply(0.0), 0.0);
+            assert_relative_eq!(Activation::Tanh.apply(-1.0), -0.7615942);
+        }
+
+        #[test]
+        fn identity() {
+            assert_relative_eq!(Activation::Identity.apply(-3.5), -3.5);
+        }
+
+        #[test]
+        fn softsign() {
+            assert_relative_eq!(Activation::Softsign.apply(1.0), 0.5);
+            assert_relative_eq!(Activation::Softsign.apply(-3.0), -0.75);
+        }
+
+        #[test]
+        fn step() {
+            assert_relative_eq!(Activation::Step.apply(-0.1), 0.0);
+            assert_relative_eq!(Activation::Step.apply(0.1), 1.0);
+        }
+
+        #[test]
+        fn custom() {
+            let activation = Activation::Custom(|x| 2.0 * x);
+
+            assert_relative_eq!(activation.apply(1.5), 3.0);
+        }
+    }
+
+    mod derivative {
+        use super::*;
+
+        // compares against a central difference of `apply`
+        fn check(activation: Activation, x: f32) {
+            let h = 1e-3;
+            let expected = (activation.apply(x + h) - activation.apply(x - h)) / (2.0 * h);
+
+            assert_relative_eq!(activation.derivative(x), expected, epsilon = 1e-2);
+        }
+
+        #[test]
+        fn relu() {
+            assert_relative_eq!(Activation::ReLU.derivative(-0.5), 0.0);
+            assert_relative_eq!(Activation::ReLU.derivative(0.5), 1.0);
+        }
+
+        #[test]
+        fn leaky_relu() {
+            assert_relative_eq!(Activation::LeakyReLU(0.1).derivative(-0.5), 0.1);
+            assert_relative_eq!(Activation::LeakyReLU(0.1).derivative(0.5), 1.0);
+        }
+
+        #[test]
+        fn smooth() {
+            for activation in [Activation::Sigmoid, Activation::Tanh, Activation::Identity, Activation::Softsign] {
+                for x in [-2.0, -0.3, 0.0, 0.7, 1.5] {
+                    check(activation, x);
+                }
+            }
+        }
+
+        #[test]
+        fn step() {
+            assert_relative_eq!(Activation::Step.derivative(-0.1), 0.0);
+            assert_relative_eq!(Activation::Step.derivative(0.1), 0.0);
+        }
+
+        #[test]
+        fn custom() {
+            let activation = Activation::Custom(|x| x * x);
+
+            assert_relative_eq!(activation.derivative(1.5), 3.0, epsilon = 1e-2);
+        }
+    }
+}
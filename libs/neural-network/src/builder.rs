@@ -0,0 +1,489 @@
+use std::marker::PhantomData;
+
+use super::*;
+
+pub struct NetworkBuilder<F = f32> {
+    layers: Vec<LayerTopology>,
+    initializer: Initializer,
+    scalar: PhantomData<F>,
+}
+
+impl NetworkBuilder {
+    pub fn new(inputs: usize) -> Self {
+        Self::from_topology(&[LayerTopology {
+            neurons: inputs,
+            activation: Activation::Identity,
+            recurrent: false,
+            skips: Vec::new(),
+        }])
+    }
+
+    pub fn from_topology(layers: &[LayerTopology]) -> Self {
+        Self {
+            layers: layers.to_vec(),
+            initializer: Initializer::default(),
+            scalar: PhantomData,
+        }
+    }
+}
+
+impl<F: Float> NetworkBuilder<F> {
+    // e.g. `.scalar::<f64>()` to build an `f64` network
+    pub fn scalar<G: Float>(self) -> NetworkBuilder<G> {
+        NetworkBuilder {
+            layers: self.layers,
+            initializer: self.initializer,
+            scalar: PhantomData,
+        }
+    }
+
+    // only affects `build_random`
+    pub fn initializer(mut self, initializer: Initializer) -> Self {
+        self.initializer = initializer;
+        self
+    }
+
+    pub fn layer(mut self, neurons: usize, activation: Activation) -> Self {
+        self.layers.push(LayerTopology {
+            neurons,
+            activation,
+            recurrent: false,
+            skips: Vec::new(),
+        });
+        self
+    }
+
+    pub fn recurrent_layer(mut self, neurons: usize, activation: Activation) -> Self {
+        self.layers.push(LayerTopology {
+            neurons,
+            activation,
+            recurrent: true,
+            skips: Vec::new(),
+        });
+        self
+    }
+
+    // feeds the outputs of an earlier layer (0 = input layer)
+    // into the most recently added one, see `LayerTopology::skips`
+    pub fn skip_from(mut self, layer: usize) -> Self {
+        // without any layers there's nothing to skip into, and building
+        // fails with `NotEnoughLayers` anyway
+        if let Some(last) = self.layers.last_mut() {
+            last.skips.push(layer);
+        }
+
+        self
+    }
+
+    pub fn topology(&self) -> &[LayerTopology] {
+        &self.layers
+    }
+
+    // number of weights (biases included) a network with this topology has;
+    // fails the same way the `build_*` methods do
+    pub fn weights_count(&self) -> Result<usize, NetworkError> {
+        self.validate()?;
+
+        Ok(self.valid_weights_count())
+    }
+
+    // `weights_count`, for topologies that passed `validate`
+    fn valid_weights_count(&self) -> usize {
+        (1..self.layers.len())
+            .map(|layer| (self.synapses(layer) + 1) * self.layers[layer].neurons)
+            .sum()
+    }
+
+    pub fn validate(&self) -> Result<(), NetworkError> {
+        if self.layers.len() < 2 {
+            return Err(NetworkError::NotEnoughLayers {
+                layers: self.layers.len(),
+            });
+        }
+
+        if let Some(layer) = self.layers.iter().position(|layer| layer.neurons == 0) {
+            return Err(NetworkError::EmptyLayer { layer });
+        }
+
+        for (layer, topology) in self.layers.iter().enumerate() {
+            for (idx, &from) in topology.skips.iter().enumerate() {
+                // the previous layer is always connected already
+                if from + 1 >= layer || topology.skips[..idx].contains(&from) {
+                    return Err(NetworkError::InvalidSkip { layer, from });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn build_random(
+        &self,
+        rng: &mut dyn rand::RngCore
+    ) -> Result<Network<F>, NetworkError> {
+        self.validate()?;
+
+        let layers = self.layers
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(idx, layer)| {
+                layer::Layer::randomize(
+                    self.inputs(idx),
+                    layer.neurons,
+                    layer.activation,
+                    layer.recurrent,
+                    &self.initializer,
+                    rng
+                )
+                .with_skips(&layer.skips)
+            })
+            .collect();
+
+        Ok(Network { layers })
+    }
+
+    pub fn build_from_weights(
+        &self,
+        weights: impl IntoIterator<Item = F>
+    ) -> Result<Network<F>, NetworkError> {
+        self.validate()?;
+
+        let weights: Vec<_> = weights
+            .into_iter()
+            .collect();
+
+        self.check_weights_count(weights.len())?;
+
+        let mut weights = weights.into_iter();
+
+        let layers = self.layers
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(idx, layer)| {
+                layer::Layer::from_weights(
+                    self.inputs(idx),
+                    layer.neurons,
+                    layer.activation,
+                    layer.recurrent,
+                    &mut weights
+                )
+                .with_skips(&layer.skips)
+            })
+            .collect();
+
+        Ok(Network { layers })
+    }
+
+    fn check_weights_count(&self, actual: usize) -> Result<(), NetworkError> {
+        let expected = self.valid_weights_count();
+
+        if actual > expected {
+            return Err(NetworkError::TooManyWeights { expected, actual });
+        }
+
+        // find the first neuron that cannot be fully built
+        let mut offset = 0;
+
+        for layer in 1..self.layers.len() {
+            for neuron in 0..self.layers[layer].neurons {
+                offset += self.synapses(layer) + 1;
+
+                if offset > actual {
+                    return Err(NetworkError::NotEnoughWeights {
+                        layer,
+                        neuron,
+                        short: offset - actual,
+                        missing: expected - actual,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // outputs of the previous layer plus those of the skipped-from ones
+    fn inputs(&self, layer: usize) -> usize {
+        let skips: usize = self.layers[layer].skips
+            .iter()
+            .map(|&from| self.layers[from].neurons)
+            .sum();
+
+        self.layers[layer - 1].neurons + skips
+    }
+
+    fn synapses(&self, layer: usize) -> usize {
+        layer::Layer::<F>::synapses(
+            self.inputs(layer),
+            self.layers[layer].neurons,
+            self.layers[layer].recurrent
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    fn builder() -> NetworkBuilder {
+        NetworkBuilder::new(3)
+            .layer(2, Activation::ReLU)
+            .layer(1, Activation::Tanh)
+    }
+
+    mod build_random {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = builder()
+                .build_random(&mut rng)
+                .unwrap();
+
+            assert_eq!(network.layers.len(), 2);
+            assert_eq!(network.layers[0].neurons.len(), 2);
+            assert_eq!(network.layers[1].neurons.len(), 1);
+            assert_eq!(Ok(network.weights().len()), builder().weights_count());
+        }
+
+        #[test]
+        fn initializer() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = builder()
+                .initializer(Initializer::zeros().with_constant_bias(0.5))
+                .build_random(&mut rng)
+                .unwrap();
+
+            let actual = network
+                .weights();
+
+            assert_relative_eq!(actual.as_slice(), [
+                0.5, 0.0, 0.0, 0.0,
+                0.5, 0.0, 0.0, 0.0,
+                0.5, 0.0, 0.0
+            ].as_ref());
+        }
+
+        #[test]
+        fn not_enough_layers() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let result = NetworkBuilder::new(3)
+                .build_random(&mut rng);
+
+            assert_eq!(
+                result.err(),
+                Some(NetworkError::NotEnoughLayers { layers: 1 })
+            );
+        }
+
+        #[test]
+        fn empty_layer() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let result = NetworkBuilder::new(3)
+                .layer(0, Activation::ReLU)
+                .layer(1, Activation::ReLU)
+                .build_random(&mut rng);
+
+            assert_eq!(
+                result.err(),
+                Some(NetworkError::EmptyLayer { layer: 1 })
+            );
+        }
+    }
+
+    mod build_from_weights {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let weights = vec![
+                0.1, 0.2, 0.3, 0.4,
+                0.5, 0.6, 0.7, 0.8,
+                0.9, 1.0, 1.1
+            ];
+
+            let network = builder()
+                .build_from_weights(weights.clone())
+                .unwrap();
+            let actual = network
+                .weights();
+
+            assert_relative_eq!(
+                actual.as_slice(),
+                weights.as_slice()
+            );
+        }
+
+        #[test]
+        fn not_enough_weights() {
+            let result = builder()
+                .build_from_weights(vec![0.0; 6]);
+
+            assert_eq!(
+                result.as_ref().err(),
+                Some(&NetworkError::NotEnoughWeights {
+                    layer: 1,
+                    neuron: 1,
+                    short: 2,
+                    missing: 5
+                })
+            );
+
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                "not enough weights: layer 1, neuron 1 is short by 2 weight(s), 5 missing in total"
+            );
+
+            let result = builder()
+                .build_from_weights(vec![0.0; 10]);
+
+            assert_eq!(
+                result.err(),
+                Some(NetworkError::NotEnoughWeights {
+                    layer: 2,
+                    neuron: 0,
+                    short: 1,
+                    missing: 1
+                })
+            );
+        }
+
+        #[test]
+        fn recurrent() {
+            let builder = NetworkBuilder::new(2)
+                .recurrent_layer(3, Activation::Tanh)
+                .layer(1, Activation::Identity);
+
+            // (2 inputs + 3 recurrent + bias) * 3 + (3 inputs + bias) * 1
+            assert_eq!(builder.weights_count(), Ok(22));
+
+            let result = builder
+                .build_from_weights(vec![0.0; 10]);
+
+            assert_eq!(
+                result.err(),
+                Some(NetworkError::NotEnoughWeights {
+                    layer: 1,
+                    neuron: 1,
+                    short: 2,
+                    missing: 12
+                })
+            );
+        }
+
+        #[test]
+        fn too_many_weights() {
+            let result = builder()
+                .build_from_weights(vec![0.0; 12]);
+
+            assert_eq!(
+                result.err(),
+                Some(NetworkError::TooManyWeights {
+                    expected: 11,
+                    actual: 12
+                })
+            );
+        }
+    }
+
+    mod scalar {
+        use super::*;
+
+        #[test]
+        fn f64() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network_f32 = builder()
+                .build_random(&mut rng)
+                .unwrap();
+            let network = builder()
+                .scalar::<f64>()
+                .build_from_weights(network_f32.weights().into_iter().map(f64::from))
+                .unwrap();
+
+            let output: Vec<f64> = network
+                .propagate(vec![0.3, -0.1, 0.7]);
+            let output_f32 = network_f32
+                .propagate(vec![0.3, -0.1, 0.7]);
+
+            assert_relative_eq!(output[0] as f32, output_f32[0], epsilon = 1e-6);
+        }
+
+        #[test]
+        fn keeps_settings() {
+            let network = builder()
+                .initializer(Initializer::zeros())
+                .scalar::<f64>()
+                .build_from_weights(vec![0.5; 11])
+                .unwrap();
+
+            assert_eq!(network.topology().len(), 3);
+            assert_eq!(network.weights(), vec![0.5_f64; 11]);
+        }
+    }
+
+    mod skip_from {
+        use super::*;
+
+        #[test]
+        fn weights_count() {
+            let builder = NetworkBuilder::new(3)
+                .layer(2, Activation::ReLU)
+                .layer(4, Activation::ReLU)
+                .layer(1, Activation::Tanh)
+                .skip_from(0)
+                .skip_from(1);
+
+            // (3 + 1) * 2 + (2 + 1) * 4 + (4 + 3 + 2 + 1) * 1
+            assert_eq!(builder.weights_count(), Ok(30));
+            assert_eq!(builder.topology()[3].skips, vec![0, 1]);
+        }
+
+        #[test]
+        fn invalid() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            for (from, skips) in [(1, vec![1]), (2, vec![2]), (0, vec![0, 0]), (5, vec![5])] {
+                let builder = skips
+                    .into_iter()
+                    .fold(builder(), |builder, skip| builder.skip_from(skip));
+
+                assert_eq!(
+                    builder.build_random(&mut rng).err(),
+                    Some(NetworkError::InvalidSkip { layer: 2, from })
+                );
+                assert_eq!(
+                    builder.weights_count(),
+                    Err(NetworkError::InvalidSkip { layer: 2, from })
+                );
+            }
+        }
+
+        #[test]
+        fn without_layers() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            assert_eq!(
+                NetworkBuilder::from_topology(&[])
+                    .skip_from(0)
+                    .build_random(&mut rng)
+                    .err(),
+                Some(NetworkError::NotEnoughLayers { layers: 0 })
+            );
+
+            assert_eq!(
+                NetworkBuilder::new(2)
+                    .skip_from(0)
+                    .layer(1, Activation::Identity)
+                    .build_random(&mut rng)
+                    .err(),
+                Some(NetworkError::InvalidSkip { layer: 0, from: 0 })
+            );
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use rand::{Rng, RngCore};
+
+use super::*;
+
+// Step size used unless `Ctrnn::dt` says otherwise
+pub const DEFAULT_DT: f32 = 0.1;
+
+// Evolvable parameters per neuron, besides its weights:
+// time constant, bias and gain
+const NEURON_PARAMS: usize = 3;
+
+// Continuous-time recurrent neural network (Beer, 1995).
+//
+// Every neuron has a leaky state `y` that follows
+//
+//   tau * dy/dt = -y + sum(w * act(gain * (y' + bias'))) + sum(v * input)
+//
+// over all neurons (w) and inputs (v), integrated with Euler steps of
+// `dt`. Unlike `Network`, outputs change gradually over many steps.
+//
+// Outputs are the firing rates of the last `outputs` neurons
+#[derive(Clone, Debug)]
+pub struct Ctrnn {
+    inputs: usize,
+    outputs: usize,
+    neurons: Vec<CtrnnNeuron>,
+    activation: Activation,
+    dt: f32,
+}
+
+#[derive(Clone, Debug)]
+struct CtrnnNeuron {
+    time_constant: f32,
+    bias: f32,
+    gain: f32,
+    // one per input
+    input_weights: Vec<f32>,
+    // one per neuron, itself included
+    weights: Vec<f32>,
+}
+
+// Neuron states, kept outside of `Ctrnn` the same way as `NetworkState`
+#[derive(Clone, Debug)]
+pub struct CtrnnState {
+    values: Vec<f32>,
+}
+
+impl CtrnnState {
+    pub fn reset(&mut self) {
+        self.values.fill(0.0);
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+impl Ctrnn {
+    pub fn randomize(
+        inputs: usize,
+        neurons: usize,
+        outputs: usize,
+        rng: &mut dyn RngCore
+    ) -> Self {
+        Self::assert_sizes(neurons, outputs);
+
+        let neurons = (0..neurons)
+            .map(|_| CtrnnNeuron {
+                time_constant: rng.gen_range(0.5..=5.0),
+                bias: rng.gen_range(-1.0..=1.0),
+                gain: 1.0,
+                input_weights: (0..inputs).map(|_| rng.gen_range(-1.0..=1.0)).collect(),
+                weights: (0..neurons).map(|_| rng.gen_range(-1.0..=1.0)).collect(),
+            })
+            .collect();
+
+        Self::new(inputs, outputs, neurons)
+    }
+
+    // Inverse of `params`; expects exactly `params_count` values.
+    //
+    // Errors count the neurons as layer 1, the inputs being layer 0
+    pub fn from_params(
+        inputs: usize,
+        neurons: usize,
+        outputs: usize,
+        params: impl IntoIterator<Item = f32>
+    ) -> Result<Self, NetworkError> {
+        Self::check_sizes(neurons, outputs)?;
+
+        let params: Vec<f32> = params.into_iter().collect();
+        let expected = Self::params_count(inputs, neurons);
+        let actual = params.len();
+
+        if actual > expected {
+            return Err(NetworkError::TooManyWeights { expected, actual });
+        }
+
+        if actual < expected {
+            let per_neuron = expected / neurons;
+            let neuron = actual / per_neuron;
+
+            return Err(NetworkError::NotEnoughWeights {
+                layer: 1,
+                neuron,
+                short: (neuron + 1) * per_neuron - actual,
+                missing: expected - actual,
+            });
+        }
+
+        let mut params = params.into_iter();
+        let mut next = || params.next().unwrap();
+
+        let neurons = (0..neurons)
+            .map(|_| CtrnnNeuron {
+                time_constant: next(),
+                bias: next(),
+                gain: next(),
+                input_weights: (0..inputs).map(|_| next()).collect(),
+                weights: (0..neurons).map(|_| next()).collect(),
+            })
+            .collect();
+
+        Ok(Self::new(inputs, outputs, neurons))
+    }
+
+    pub fn params_count(inputs: usize, neurons: usize) -> usize {
+        neurons * (NEURON_PARAMS + inputs + neurons)
+    }
+
+    // Every evolvable parameter, ready to be used as a `Chromosome`.
+    //
+    // Per neuron: time constant, bias, gain, input weights,
+    // then the weights of every neuron's output
+    pub fn params(&self) -> Vec<f32> {
+        self.neurons
+            .iter()
+            .flat_map(|neuron| {
+                [neuron.time_constant, neuron.bias, neuron.gain]
+                    .into_iter()
+                    .chain(neuron.input_weights.iter().cloned())
+                    .chain(neuron.weights.iter().cloned())
+            })
+            .collect()
+    }
+
+    // Sigmoid by default
+    pub fn activation(mut self, activation: Activation) -> Self {
+        self.activation = activation;
+        self
+    }
+
+    pub fn dt(mut self, dt: f32) -> Self {
+        assert!(dt > 0.0);
+
+        self.dt = dt;
+        self
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    pub fn state(&self) -> CtrnnState {
+        CtrnnState {
+            values: vec![0.0; self.neurons.len()],
+        }
+    }
+
+    // Advances `state` by a single `dt`
+    pub fn step(&self, inputs: &[f32], state: &mut CtrnnState) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.inputs);
+        assert_eq!(state.values.len(), self.neurons.len());
+
+        let firing = self.firing(state);
+
+        for (value, neuron) in state.values.iter_mut().zip(&self.neurons) {
+            let input = neuron.input_weights
+                .iter()
+                .zip(inputs)
+                .map(|(weight, input)| weight * input)
+                .sum::<f32>();
+
+            let recurrent = neuron.weights
+                .iter()
+                .zip(&firing)
+                .map(|(weight, firing)| weight * firing)
+                .sum::<f32>();
+
+            *value += self.dt * (input + recurrent - *value) / self.time_constant(neuron);
+        }
+
+        let firing = self.firing(state);
+
+        firing[firing.len() - self.outputs..].to_vec()
+    }
+
+    fn new(inputs: usize, outputs: usize, neurons: Vec<CtrnnNeuron>) -> Self {
+        Self {
+            inputs,
+            outputs,
+            neurons,
+            activation: Activation::Sigmoid,
+            dt: DEFAULT_DT,
+        }
+    }
+
+    fn assert_sizes(neurons: usize, outputs: usize) {
+        Self::check_sizes(neurons, outputs)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    fn check_sizes(neurons: usize, outputs: usize) -> Result<(), NetworkError> {
+        if outputs == 0 || outputs > neurons {
+            return Err(NetworkError::InvalidOutputs { outputs, neurons });
+        }
+
+        Ok(())
+    }
+
+    fn firing(&self, state: &CtrnnState) -> Vec<f32> {
+        state.values
+            .iter()
+            .zip(&self.neurons)
+            .map(|(value, neuron)| self.activation.apply(neuron.gain * (value + neuron.bias)))
+            .collect()
+    }
+
+    // Evolved time constants can be anything, so they're taken as
+    // magnitudes and kept at or above `dt` - anything faster would
+    // make the Euler steps overshoot
+    fn time_constant(&self, neuron: &CtrnnNeuron) -> f32 {
+        neuron.time_constant.abs().max(self.dt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    mod params {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let ctrnn = Ctrnn::randomize(3, 4, 2, &mut rng);
+            let params = ctrnn.params();
+
+            assert_eq!(params.len(), Ctrnn::params_count(3, 4));
+            assert_eq!(Ctrnn::from_params(3, 4, 2, params.clone()).unwrap().params(), params);
+        }
+
+        #[test]
+        fn layout() {
+            let ctrnn = Ctrnn::from_params(1, 1, 1, vec![2.0, 0.5, 1.5, 0.25, -1.0]).unwrap();
+
+            assert_relative_eq!(ctrnn.neurons[0].time_constant, 2.0);
+            assert_relative_eq!(ctrnn.neurons[0].bias, 0.5);
+            assert_relative_eq!(ctrnn.neurons[0].gain, 1.5);
+            assert_eq!(ctrnn.neurons[0].input_weights, vec![0.25]);
+            assert_eq!(ctrnn.neurons[0].weights, vec![-1.0]);
+        }
+
+        #[test]
+        fn too_many() {
+            assert_eq!(
+                Ctrnn::from_params(1, 1, 1, vec![0.0; 6]).err(),
+                Some(NetworkError::TooManyWeights { expected: 5, actual: 6 })
+            );
+        }
+
+        #[test]
+        fn not_enough() {
+            // 6 params per neuron
+            assert_eq!(
+                Ctrnn::from_params(1, 2, 1, vec![0.0; 8]).err(),
+                Some(NetworkError::NotEnoughWeights { layer: 1, neuron: 1, short: 4, missing: 4 })
+            );
+        }
+
+        #[test]
+        fn no_outputs() {
+            assert_eq!(
+                Ctrnn::from_params(1, 1, 0, vec![0.0; 5]).err(),
+                Some(NetworkError::InvalidOutputs { outputs: 0, neurons: 1 })
+            );
+        }
+
+        #[test]
+        fn more_outputs_than_neurons() {
+            assert_eq!(
+                Ctrnn::from_params(1, 1, 2, vec![0.0; 5]).err(),
+                Some(NetworkError::InvalidOutputs { outputs: 2, neurons: 1 })
+            );
+        }
+    }
+
+    mod step {
+        use super::*;
+
+        #[test]
+        fn leaky_integration() {
+            // tau = 2, input weight 1, no recurrence
+            let ctrnn = Ctrnn::from_params(1, 1, 1, vec![2.0, 0.0, 1.0, 1.0, 0.0])
+                .unwrap()
+                .activation(Activation::Identity)
+                .dt(0.5);
+
+            let mut state = ctrnn.state();
+
+            for step in 1..=5 {
+                let outputs = ctrnn.step(&[1.0], &mut state);
+
+                // y(t) = 1 - (1 - dt / tau)^t
+                assert_relative_eq!(outputs[0], 1.0 - 0.75_f32.powi(step));
+            }
+        }
+
+        #[test]
+        fn recurrence() {
+            // the first neuron drives the second one
+            let ctrnn = Ctrnn::from_params(1, 2, 1, vec![
+                1.0, 0.0, 1.0, 1.0, 0.0, 0.0,
+                1.0, 0.0, 1.0, 0.0, 2.0, 0.0,
+            ])
+                .unwrap()
+                .activation(Activation::Identity)
+                .dt(0.5);
+
+            let mut state = ctrnn.state();
+
+            assert_relative_eq!(ctrnn.step(&[1.0], &mut state)[0], 0.0);
+            assert_relative_eq!(state.values(), [0.5, 0.0].as_ref());
+
+            assert_relative_eq!(ctrnn.step(&[1.0], &mut state)[0], 0.5);
+            assert_relative_eq!(state.values(), [0.75, 0.5].as_ref());
+        }
+
+        #[test]
+        fn fast_neurons_stay_stable() {
+            // tau below dt, or negative, would overshoot without the clamp
+            for time_constant in [0.01, -0.01] {
+                let ctrnn = Ctrnn::from_params(1, 1, 1, vec![time_constant, 0.0, 1.0, 1.0, 0.0])
+                    .unwrap()
+                    .activation(Activation::Identity);
+
+                let mut state = ctrnn.state();
+
+                for _ in 0..10 {
+                    assert_relative_eq!(ctrnn.step(&[1.0], &mut state)[0], 1.0);
+                }
+            }
+        }
+
+        #[test]
+        fn reset() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let ctrnn = Ctrnn::randomize(2, 3, 1, &mut rng);
+            let mut state = ctrnn.state();
+
+            let first = ctrnn.step(&[0.5, -0.5], &mut state);
+            ctrnn.step(&[0.5, -0.5], &mut state);
+            state.reset();
+
+            assert_eq!(ctrnn.step(&[0.5, -0.5], &mut state), first);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distance {
+    // sum of absolute differences
+    L1,
+    // euclidean
+    L2,
+    // 1 - cosine similarity, in 0..=2; ignores magnitudes
+    Cosine,
+}
+
+impl Distance {
+    // Works on any two same-length vectors, e.g. raw genomes
+    pub fn between<F: Float>(&self, a: &[F], b: &[F]) -> F {
+        assert_eq!(a.len(), b.len());
+
+        let pairs = a.iter().zip(b);
+
+        match self {
+            Self::L1 => pairs
+                .map(|(&a, &b)| (a - b).abs())
+                .sum(),
+            Self::L2 => pairs
+                .map(|(&a, &b)| (a - b) * (a - b))
+                .sum::<F>()
+                .sqrt(),
+            Self::Cosine => {
+                let dot = pairs.map(|(&a, &b)| a * b).sum::<F>();
+                let norm_a = a.iter().map(|&a| a * a).sum::<F>().sqrt();
+                let norm_b = b.iter().map(|&b| b * b).sum::<F>().sqrt();
+
+                // a zero vector points nowhere; call it equal
+                // only to another zero vector
+                if norm_a == F::zero() || norm_b == F::zero() {
+                    return if norm_a == norm_b { F::zero() } else { F::one() };
+                }
+
+                F::one() - dot / (norm_a * norm_b)
+            }
+        }
+    }
+}
+
+impl<F: Float> Network<F> {
+    // Distance between the weights (biases included) of two networks
+    // of the same topology
+    pub fn distance(&self, other: &Self, distance: Distance) -> F {
+        assert!(self.same_shape(other), "networks differ in topology");
+
+        distance.between(&self.weights(), &other.weights())
+    }
+
+    // How differently two networks act: mean distance between their
+    // outputs over `probes`. Only the numbers of inputs and outputs need
+    // to match, and recurrent layers see a zeroed state
+    pub fn behavioural_distance(&self, other: &Self, probes: &[Vec<F>], distance: Distance) -> F {
+        if probes.is_empty() {
+            return F::zero();
+        }
+
+        let total: F = probes
+            .iter()
+            .map(|probe| distance.between(
+                &self.propagate(probe.clone()),
+                &other.propagate(probe.clone())
+            ))
+            .sum();
+
+        total / F::from_f64(probes.len() as f64)
+    }
+
+    fn same_shape(&self, other: &Self) -> bool {
+        self.layers.len() == other.layers.len()
+            && self.layers
+                .iter()
+                .zip(&other.layers)
+                .all(|(a, b)| {
+                    a.neurons.len() == b.neurons.len()
+                        && a.inputs() == b.inputs()
+                        && a.recurrent == b.recurrent
+                        && a.skips == b.skips
+                })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    fn network(weights: Vec<f32>) -> Network {
+        Network::from_weights(
+            &[
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    activation: Activation::Identity,
+                    ..Default::default()
+                },
+            ],
+            weights
+        )
+    }
+
+    mod between {
+        use super::*;
+
+        #[test]
+        fn l1() {
+            assert_relative_eq!(Distance::L1.between(&[1.0, -2.0, 0.5], &[0.0, 1.0, 0.5]), 4.0);
+        }
+
+        #[test]
+        fn l2() {
+            assert_relative_eq!(Distance::L2.between(&[3.0, 0.0], &[0.0, 4.0]), 5.0);
+        }
+
+        #[test]
+        fn cosine() {
+            assert_relative_eq!(Distance::Cosine.between(&[1.0, 1.0], &[2.0, 2.0]), 0.0);
+            assert_relative_eq!(Distance::Cosine.between(&[1.0, 0.0], &[0.0, 3.0]), 1.0);
+            assert_relative_eq!(Distance::Cosine.between(&[1.0, -1.0], &[-1.0, 1.0]), 2.0);
+        }
+
+        #[test]
+        fn cosine_zero() {
+            assert_eq!(Distance::Cosine.between(&[0.0, 0.0], &[0.0, 0.0]), 0.0);
+            assert_eq!(Distance::Cosine.between(&[0.0, 0.0], &[1.0, 0.0]), 1.0);
+        }
+    }
+
+    mod distance {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let a = network(vec![0.0, 1.0, 2.0]);
+            let b = network(vec![1.0, 1.0, 0.0]);
+
+            assert_relative_eq!(a.distance(&b, Distance::L1), 3.0);
+            assert_relative_eq!(a.distance(&b, Distance::L2), 5.0_f32.sqrt());
+            assert_relative_eq!(a.distance(&a, Distance::L2), 0.0);
+        }
+
+        #[test]
+        #[should_panic(expected = "networks differ in topology")]
+        fn different_topology() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let other = Network::builder(2)
+                .layer(2, Activation::Identity)
+                .layer(1, Activation::Identity)
+                .build_random(&mut rng)
+                .unwrap();
+
+            network(vec![0.0; 3]).distance(&other, Distance::L1);
+        }
+    }
+
+    mod behavioural_distance {
+        use super::*;
+
+        #[test]
+        fn test() {
+            // `b` outputs 1 more than `a`, whatever the input
+            let a = network(vec![0.0, 1.0, -1.0]);
+            let b = network(vec![1.0, 1.0, -1.0]);
+            let probes = vec![vec![0.5, 0.25], vec![-1.0, 2.0]];
+
+            assert_relative_eq!(a.behavioural_distance(&b, &probes, Distance::L1), 1.0);
+            assert_relative_eq!(a.behavioural_distance(&a, &probes, Distance::L2), 0.0);
+        }
+
+        #[test]
+        fn ignores_topology() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let deeper = Network::builder(2)
+                .layer(3, Activation::ReLU)
+                .layer(1, Activation::Identity)
+                .build_from_weights(vec![
+                    0.0, 1.0, 0.0,
+                    0.0, -1.0, 0.0,
+                    0.0, 0.0, 1.0,
+                    0.0, 1.0, -1.0, 0.0
+                ])
+                .unwrap();
+
+            // relu(x) - relu(-x) = x
+            let identity = network(vec![0.0, 1.0, 0.0]);
+            let probes = vec![vec![0.5, 0.25], vec![-1.0, 2.0]];
+
+            assert_relative_eq!(identity.behavioural_distance(&deeper, &probes, Distance::L1), 0.0);
+
+            let random = Network::builder(2)
+                .layer(1, Activation::Identity)
+                .build_random(&mut rng)
+                .unwrap();
+
+            assert!(identity.behavioural_distance(&random, &probes, Distance::L1) > 0.0);
+        }
+
+        #[test]
+        fn no_probes() {
+            let a = network(vec![0.0, 1.0, -1.0]);
+
+            assert_eq!(a.behavioural_distance(&a, &[], Distance::L2), 0.0);
+        }
+    }
+}
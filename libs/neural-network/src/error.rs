@@ -0,0 +1,149 @@
+use std::fmt;
+
+// Layers are indexed the same way as the `LayerTopology` slice,
+// i.e. layer 0 is the input layer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetworkError {
+    NotEnoughLayers {
+        layers: usize,
+    },
+    EmptyLayer {
+        layer: usize,
+    },
+    // `neuron` is the first one that could not be built and `short` how
+    // many of its own weights are missing; `missing` is how many are
+    // missing over the whole network
+    NotEnoughWeights {
+        layer: usize,
+        neuron: usize,
+        short: usize,
+        missing: usize,
+    },
+    TooManyWeights {
+        expected: usize,
+        actual: usize,
+    },
+    // plasticity coefficients that don't fit the network's connections
+    CoefficientCount {
+        expected: usize,
+        actual: usize,
+    },
+    // outputs of a `Ctrnn` are some of its neurons, at least one
+    InvalidOutputs {
+        outputs: usize,
+        neurons: usize,
+    },
+    // skips must come from a layer before the previous one,
+    // at most once per layer
+    InvalidSkip {
+        layer: usize,
+        from: usize,
+    },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughLayers { layers } => write!(
+                f,
+                "network needs at least 2 layers, got {}",
+                layers
+            ),
+            Self::EmptyLayer { layer } => write!(
+                f,
+                "layer {} has no neurons",
+                layer
+            ),
+            Self::NotEnoughWeights { layer, neuron, short, missing } => write!(
+                f,
+                "not enough weights: layer {}, neuron {} is short by {} weight(s), {} missing in total",
+                layer, neuron, short, missing
+            ),
+            Self::TooManyWeights { expected, actual } => write!(
+                f,
+                "too many weights: expected {}, got {}",
+                expected, actual
+            ),
+            Self::CoefficientCount { expected, actual } => write!(
+                f,
+                "wrong number of plasticity coefficients: expected {}, got {}",
+                expected, actual
+            ),
+            Self::InvalidOutputs { outputs, neurons } => write!(
+                f,
+                "cannot take {} output(s) from {} neuron(s)",
+                outputs, neurons
+            ),
+            Self::InvalidSkip { layer, from } => write!(
+                f,
+                "layer {} cannot take a skip connection from layer {}",
+                layer, from
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+#[derive(Debug)]
+pub enum FormatError {
+    Json(serde_json::Error),
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    // bytes per weight other than 4 or 8
+    UnsupportedScalar(u8),
+    UnexpectedEof,
+    TrailingData,
+    UnknownActivation(u8),
+    // `Activation::Custom` is a function pointer and cannot be stored
+    CustomActivation {
+        layer: usize,
+    },
+    Network(NetworkError),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid json: {}", err),
+            Self::InvalidMagic => write!(f, "not a network file"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported format version {}",
+                version
+            ),
+            Self::UnsupportedScalar(size) => write!(
+                f,
+                "unsupported scalar size of {} bytes",
+                size
+            ),
+            Self::UnexpectedEof => write!(f, "unexpected end of data"),
+            Self::TrailingData => write!(f, "unexpected data after the network"),
+            Self::UnknownActivation(tag) => write!(
+                f,
+                "unknown activation tag {}",
+                tag
+            ),
+            Self::CustomActivation { layer } => write!(
+                f,
+                "layer {} uses a custom activation, which cannot be serialized",
+                layer
+            ),
+            Self::Network(err) => write!(f, "invalid network: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<NetworkError> for FormatError {
+    fn from(err: NetworkError) -> Self {
+        Self::Network(err)
+    }
+}
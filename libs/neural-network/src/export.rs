@@ -0,0 +1,375 @@
+use std::fmt::Write;
+
+use super::*;
+
+// SVG layout, in pixels
+const COLUMN_WIDTH: f32 = 160.0;
+const ROW_HEIGHT: f32 = 48.0;
+const MARGIN: f32 = 64.0;
+const RADIUS: f32 = 14.0;
+
+const POSITIVE: &str = "#2166ac";
+const NEGATIVE: &str = "#b2182b";
+
+// Names shown in place of the default `in 3`/`out 1`; missing entries
+// fall back to the defaults
+#[derive(Clone, Debug, Default)]
+pub struct Labels {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+impl Labels {
+    pub fn new(
+        inputs: impl IntoIterator<Item = impl Into<String>>,
+        outputs: impl IntoIterator<Item = impl Into<String>>
+    ) -> Self {
+        Self {
+            inputs: inputs.into_iter().map(Into::into).collect(),
+            outputs: outputs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// A single non-zero weight; `from` and `to` are (column, neuron), where
+// column 0 holds the inputs
+struct Edge {
+    from: (usize, usize),
+    to: (usize, usize),
+    weight: f32,
+    recurrent: bool,
+}
+
+impl<F: Float> Network<F> {
+    // Graphviz graph, one column per layer; render with `dot -Tsvg`.
+    //
+    // Edge colour tells the weight's sign (blue positive, red negative),
+    // edge width its magnitude; zeroed weights aren't drawn
+    pub fn to_dot(&self, labels: &Labels) -> String {
+        let max = self.max_weight();
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph network {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+        writeln!(dot, "    splines=true;").unwrap();
+        writeln!(dot, "    node [shape=circle, fontsize=10];").unwrap();
+
+        for column in 0..=self.layers.len() {
+            writeln!(dot, "    subgraph cluster_{} {{", column).unwrap();
+            writeln!(dot, "        style=invis;").unwrap();
+
+            for neuron in 0..self.column_size(column) {
+                writeln!(
+                    dot,
+                    "        n{}_{} [label=\"{}\"];",
+                    column,
+                    neuron,
+                    escape_dot(&self.node_label(labels, column, neuron).join("\n"))
+                ).unwrap();
+            }
+
+            writeln!(dot, "    }}").unwrap();
+        }
+
+        for edge in self.edges() {
+            writeln!(
+                dot,
+                "    n{}_{} -> n{}_{} [color=\"{}\", penwidth={:.2}{}];",
+                edge.from.0,
+                edge.from.1,
+                edge.to.0,
+                edge.to.1,
+                colour(edge.weight),
+                stroke(edge.weight, max),
+                if edge.recurrent { ", style=dashed, constraint=false" } else { "" }
+            ).unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+
+        dot
+    }
+
+    // Standalone SVG laid out the same way as `to_dot`, without the
+    // need for Graphviz
+    pub fn to_svg(&self, labels: &Labels) -> String {
+        let max = self.max_weight();
+        let columns = self.layers.len() + 1;
+        let rows = (0..columns)
+            .map(|column| self.column_size(column))
+            .max()
+            .unwrap();
+
+        let width = 2.0 * MARGIN + (columns - 1) as f32 * COLUMN_WIDTH;
+        let height = 2.0 * MARGIN + (rows - 1) as f32 * ROW_HEIGHT;
+
+        // neurons of every column are centered vertically
+        let position = |(column, neuron): (usize, usize)| {
+            let offset = (rows - self.column_size(column)) as f32 * ROW_HEIGHT / 2.0;
+
+            (
+                MARGIN + column as f32 * COLUMN_WIDTH,
+                MARGIN + offset + neuron as f32 * ROW_HEIGHT
+            )
+        };
+
+        let mut svg = String::new();
+
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+            width,
+            height
+        ).unwrap();
+        writeln!(svg, "  <rect width=\"100%\" height=\"100%\" fill=\"white\"/>").unwrap();
+
+        for edge in self.edges() {
+            let (x1, y1) = position(edge.from);
+            let (x2, y2) = position(edge.to);
+            let style = format!(
+                "stroke=\"{}\" stroke-width=\"{:.2}\" fill=\"none\"",
+                colour(edge.weight),
+                stroke(edge.weight, max)
+            );
+
+            if edge.recurrent {
+                // a neuron's loop onto itself leaves above and comes
+                // back below, or there'd be nothing to draw
+                let (y1, y2, bulge) = if edge.from == edge.to {
+                    (y1 - RADIUS / 2.0, y2 + RADIUS / 2.0, RADIUS)
+                } else {
+                    (y1, y2, 0.0)
+                };
+
+                // arcs out to the right of the layer and back
+                writeln!(
+                    svg,
+                    "  <path d=\"M {} {} C {} {}, {} {}, {} {}\" {} stroke-dasharray=\"4 2\"/>",
+                    x1 + RADIUS,
+                    y1,
+                    x1 + COLUMN_WIDTH / 3.0,
+                    y1 - bulge,
+                    x2 + COLUMN_WIDTH / 3.0,
+                    y2 + bulge,
+                    x2 + RADIUS,
+                    y2,
+                    style
+                ).unwrap();
+            } else {
+                writeln!(
+                    svg,
+                    "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {}/>",
+                    x1, y1, x2, y2, style
+                ).unwrap();
+            }
+        }
+
+        for column in 0..columns {
+            for neuron in 0..self.column_size(column) {
+                let (x, y) = position((column, neuron));
+
+                writeln!(
+                    svg,
+                    "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#f7f7f7\" stroke=\"#404040\"/>",
+                    x, y, RADIUS
+                ).unwrap();
+
+                // name above the node, bias below
+                for (idx, line) in self.node_label(labels, column, neuron).iter().enumerate() {
+                    let dy = if idx == 0 { -RADIUS - 4.0 } else { RADIUS + 12.0 };
+
+                    writeln!(
+                        svg,
+                        "  <text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"10\" text-anchor=\"middle\">{}</text>",
+                        x,
+                        y + dy,
+                        escape_xml(line)
+                    ).unwrap();
+                }
+            }
+        }
+
+        writeln!(svg, "</svg>").unwrap();
+
+        svg
+    }
+
+    fn column_size(&self, column: usize) -> usize {
+        if column == 0 {
+            self.inputs()
+        } else {
+            self.layers[column - 1].neurons.len()
+        }
+    }
+
+    // name, then the bias for non-input neurons
+    fn node_label(&self, labels: &Labels, column: usize, neuron: usize) -> Vec<String> {
+        if column == 0 {
+            let name = labels.inputs
+                .get(neuron)
+                .cloned()
+                .unwrap_or_else(|| format!("in {}", neuron));
+
+            return vec![name];
+        }
+
+        let name = if column == self.layers.len() {
+            labels.outputs
+                .get(neuron)
+                .cloned()
+                .unwrap_or_else(|| format!("out {}", neuron))
+        } else {
+            format!("h{}.{}", column, neuron)
+        };
+
+        let bias = self.layers[column - 1].neurons[neuron].bias;
+
+        vec![name, format!("b={:.2}", Float::to_f32(bias))]
+    }
+
+    fn edges(&self) -> Vec<Edge> {
+        let mut edges = Vec::new();
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            let column = idx + 1;
+
+            // same order as the weights, see `LayerTopology::skips`
+            let sources: Vec<(usize, bool)> = std::iter::once(idx)
+                .chain(layer.skips.iter().cloned())
+                .map(|source| (source, false))
+                .chain(layer.recurrent.then_some((column, true)))
+                .collect();
+
+            for (neuron, weights) in layer.neurons.iter().map(|neuron| &neuron.weights).enumerate() {
+                let mut weights = weights.iter();
+
+                for &(source, recurrent) in &sources {
+                    for from in 0..self.column_size(source) {
+                        let weight = Float::to_f32(*weights.next().unwrap());
+
+                        if weight != 0.0 {
+                            edges.push(Edge {
+                                from: (source, from),
+                                to: (column, neuron),
+                                weight,
+                                recurrent,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        edges
+    }
+
+    fn max_weight(&self) -> f32 {
+        self.layers
+            .iter()
+            .flat_map(|layer| &layer.neurons)
+            .flat_map(|neuron| &neuron.weights)
+            .map(|weight| Float::to_f32(weight.abs()))
+            .fold(0.0, f32::max)
+    }
+}
+
+fn colour(weight: f32) -> &'static str {
+    if weight > 0.0 {
+        POSITIVE
+    } else {
+        NEGATIVE
+    }
+}
+
+// 0.5 for the faintest weights up to 4 for the strongest one
+fn stroke(weight: f32, max: f32) -> f32 {
+    0.5 + 3.5 * weight.abs() / max
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network() -> Network {
+        Network::builder(2)
+            .recurrent_layer(1, Activation::Tanh)
+            .layer(1, Activation::Identity)
+            .skip_from(0)
+            .build_from_weights(vec![
+                0.1, 0.5, -1.0, 0.25,
+                -0.2, 2.0, 0.0, 0.0
+            ])
+            .unwrap()
+    }
+
+    mod to_dot {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let dot = network().to_dot(&Labels::new(["left \"eye\""], ["speed"]));
+
+            assert!(dot.starts_with("digraph network {\n"));
+            assert!(dot.contains("n0_0 [label=\"left \\\"eye\\\"\"];"));
+            assert!(dot.contains("n0_1 [label=\"in 1\"];"));
+            assert!(dot.contains("n1_0 [label=\"h1.0\\nb=0.10\"];"));
+            assert!(dot.contains("n2_0 [label=\"speed\\nb=-0.20\"];"));
+
+            assert!(dot.contains("n0_0 -> n1_0 [color=\"#2166ac\", penwidth=1.38];"));
+            assert!(dot.contains("n0_1 -> n1_0 [color=\"#b2182b\", penwidth=2.25];"));
+            assert!(dot.contains("n1_0 -> n1_0 [color=\"#2166ac\", penwidth=0.94, style=dashed, constraint=false];"));
+            assert!(dot.contains("n1_0 -> n2_0 [color=\"#2166ac\", penwidth=4.00];"));
+
+            // zeroed skip weights
+            assert!(!dot.contains("n0_0 -> n2_0"));
+            assert!(!dot.contains("n0_1 -> n2_0"));
+        }
+    }
+
+    mod to_svg {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let svg = network().to_svg(&Labels::new(["a < b"], Vec::<String>::new()));
+
+            assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+            assert!(svg.trim_end().ends_with("</svg>"));
+            assert_eq!(svg.matches("<circle").count(), 4);
+            assert_eq!(svg.matches("<line").count(), 3);
+            assert_eq!(svg.matches("<path").count(), 1);
+            assert!(svg.contains(">a &lt; b</text>"));
+            assert!(svg.contains(">out 0</text>"));
+            assert!(svg.contains(">b=-0.20</text>"));
+        }
+
+        #[test]
+        fn self_loop() {
+            let svg = network().to_svg(&Labels::default());
+
+            let start = svg.find("<path d=\"").unwrap() + "<path d=\"".len();
+            let path = &svg[start..start + svg[start..].find('"').unwrap()];
+
+            let points: Vec<f32> = path
+                .split([' ', ','])
+                .filter_map(|token| token.parse().ok())
+                .collect();
+
+            assert_eq!(points.len(), 8);
+            assert_ne!(points[..2], points[6..]);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::fmt::Debug;
+use std::iter::Sum;
+
+use rand::distributions::uniform::SampleUniform;
+use rand::{Rng, RngCore};
+use rand_distr::StandardNormal;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// Scalar type networks compute with; `f32` everywhere by default,
+// `f64` for when rounding errors must be ruled out
+pub trait Float:
+    num_traits::Float
+    + SampleUniform
+    + Sum
+    + Default
+    + Debug
+    + Serialize
+    + DeserializeOwned
+    + Send
+    + Sync
+    + 'static
+{
+    // size of a single value in the binary format
+    const BYTES: u8;
+
+    fn from_f32(value: f32) -> Self;
+    fn to_f32(self) -> f32;
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn standard_normal(rng: &mut dyn RngCore) -> Self;
+}
+
+impl Float for f32 {
+    const BYTES: u8 = 4;
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn standard_normal(rng: &mut dyn RngCore) -> Self {
+        rng.sample(StandardNormal)
+    }
+}
+
+impl Float for f64 {
+    const BYTES: u8 = 8;
+
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn standard_normal(rng: &mut dyn RngCore) -> Self {
+        rng.sample(StandardNormal)
+    }
+}
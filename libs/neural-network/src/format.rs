@@ -0,0 +1,821 @@
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+// Bump whenever the layout of either format changes;
+// older versions must keep loading
+//
+// 1: feed-forward layers only
+// 2: recurrent layers
+// 3: scalar size in the binary header
+// 4: skip connections
+pub const FORMAT_VERSION: u32 = 4;
+
+// Binary layout (all numbers little-endian):
+//
+// magic       4 bytes, "SLNN"
+// version     u32
+// scalar      u8, bytes per weight: 4 = f32, 8 = f64
+//             (since version 3, always 4 before)
+// inputs      u32
+// layers      u32
+// per layer:
+//   neurons     u32
+//   activation  u8 tag + f32 parameter (0.0 if unused)
+//   flags       u8, bit 0 = recurrent (since version 2),
+//               bit 1 = skip connections (since version 4)
+//   skips       if bit 1 is set: u32 count, then u32 per skipped-from layer
+//   per neuron: bias, then one weight per input (skip inputs included),
+//               then one weight per neuron of the layer if recurrent
+//
+// Weights are converted to the loading network's scalar type
+const MAGIC: &[u8; 4] = b"SLNN";
+const RECURRENT: u8 = 1;
+const SKIPS: u8 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct NetworkDocument<F> {
+    version: u32,
+    inputs: usize,
+    layers: Vec<LayerDocument<F>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayerDocument<F> {
+    activation: ActivationDocument,
+    #[serde(default)]
+    recurrent: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    skips: Vec<usize>,
+    neurons: Vec<NeuronDocument<F>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NeuronDocument<F> {
+    bias: F,
+    weights: Vec<F>,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    recurrent_weights: Vec<F>,
+}
+
+// Kept apart from `Activation`, so that the on-disk names stay stable
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum ActivationDocument {
+    ReLU,
+    LeakyReLU { slope: f32 },
+    Sigmoid,
+    Tanh,
+    Identity,
+    Softsign,
+    Step,
+}
+
+impl ActivationDocument {
+    fn new(activation: Activation, layer: usize) -> Result<Self, FormatError> {
+        Ok(match activation {
+            Activation::ReLU => Self::ReLU,
+            Activation::LeakyReLU(slope) => Self::LeakyReLU { slope },
+            Activation::Sigmoid => Self::Sigmoid,
+            Activation::Tanh => Self::Tanh,
+            Activation::Identity => Self::Identity,
+            Activation::Softsign => Self::Softsign,
+            Activation::Step => Self::Step,
+            Activation::Custom(_) => {
+                return Err(FormatError::CustomActivation { layer });
+            }
+        })
+    }
+
+    fn activation(&self) -> Activation {
+        match *self {
+            Self::ReLU => Activation::ReLU,
+            Self::LeakyReLU { slope } => Activation::LeakyReLU(slope),
+            Self::Sigmoid => Activation::Sigmoid,
+            Self::Tanh => Activation::Tanh,
+            Self::Identity => Activation::Identity,
+            Self::Softsign => Activation::Softsign,
+            Self::Step => Activation::Step,
+        }
+    }
+
+    fn tag(&self) -> (u8, f32) {
+        match *self {
+            Self::ReLU => (0, 0.0),
+            Self::LeakyReLU { slope } => (1, slope),
+            Self::Sigmoid => (2, 0.0),
+            Self::Tanh => (3, 0.0),
+            Self::Identity => (4, 0.0),
+            Self::Softsign => (5, 0.0),
+            Self::Step => (6, 0.0),
+        }
+    }
+
+    fn from_tag(tag: u8, param: f32) -> Result<Self, FormatError> {
+        Ok(match tag {
+            0 => Self::ReLU,
+            1 => Self::LeakyReLU { slope: param },
+            2 => Self::Sigmoid,
+            3 => Self::Tanh,
+            4 => Self::Identity,
+            5 => Self::Softsign,
+            6 => Self::Step,
+            _ => return Err(FormatError::UnknownActivation(tag)),
+        })
+    }
+}
+
+impl<F: Float> NetworkDocument<F> {
+    fn new(network: &Network<F>) -> Result<Self, FormatError> {
+        let layers = network.layers
+            .iter()
+            .enumerate()
+            .map(|(idx, layer)| {
+                let inputs = layer.inputs();
+
+                Ok(LayerDocument {
+                    activation: ActivationDocument::new(layer.activation, idx + 1)?,
+                    recurrent: layer.recurrent,
+                    skips: layer.skips.clone(),
+                    neurons: layer.neurons
+                        .iter()
+                        .map(|neuron| NeuronDocument {
+                            bias: neuron.bias,
+                            weights: neuron.weights[..inputs].to_vec(),
+                            recurrent_weights: neuron.weights[inputs..].to_vec(),
+                        })
+                        .collect(),
+                })
+            })
+            .collect::<Result<_, FormatError>>()?;
+
+        Ok(Self {
+            version: FORMAT_VERSION,
+            inputs: network.inputs(),
+            layers,
+        })
+    }
+
+    fn into_network(self) -> Result<Network<F>, FormatError> {
+        let mut topology = vec![LayerTopology {
+            neurons: self.inputs,
+            activation: Activation::Identity,
+            recurrent: false,
+            skips: Vec::new(),
+        }];
+        let mut weights = Vec::new();
+        // (layer, neuron, short by) of the first short neuron,
+        // and how many weights are missing over all of them
+        let mut first_short = None;
+        let mut missing = 0;
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            let inputs = skipped_inputs(&topology, idx + 1, &layer.skips)?;
+            let recurrent_inputs = if layer.recurrent {
+                layer.neurons.len()
+            } else {
+                0
+            };
+
+            for (neuron_idx, neuron) in layer.neurons.iter().enumerate() {
+                // the flat weights alone cannot tell
+                // a short neuron from a long one next to it
+                let short = shortfall(inputs, neuron.weights.len())?
+                    + shortfall(recurrent_inputs, neuron.recurrent_weights.len())?;
+
+                if short > 0 {
+                    first_short.get_or_insert((idx + 1, neuron_idx, short));
+                    missing += short;
+                }
+
+                weights.push(neuron.bias);
+                weights.extend_from_slice(&neuron.weights);
+                weights.extend_from_slice(&neuron.recurrent_weights);
+            }
+
+            topology.push(LayerTopology {
+                neurons: layer.neurons.len(),
+                activation: layer.activation.activation(),
+                recurrent: layer.recurrent,
+                skips: layer.skips.clone(),
+            });
+        }
+
+        if let Some((layer, neuron, short)) = first_short {
+            return Err(NetworkError::NotEnoughWeights { layer, neuron, short, missing }.into());
+        }
+
+        Ok(NetworkBuilder::from_topology(&topology)
+            .scalar::<F>()
+            .build_from_weights(weights)?)
+    }
+}
+
+// inputs of `layer`, given the layers before it
+fn skipped_inputs(
+    topology: &[LayerTopology],
+    layer: usize,
+    skips: &[usize]
+) -> Result<usize, NetworkError> {
+    let mut inputs = topology[layer - 1].neurons;
+
+    for &from in skips {
+        if from + 1 >= layer {
+            return Err(NetworkError::InvalidSkip { layer, from });
+        }
+
+        inputs += topology[from].neurons;
+    }
+
+    Ok(inputs)
+}
+
+// how many of a neuron's `expected` weights are missing
+fn shortfall(expected: usize, actual: usize) -> Result<usize, NetworkError> {
+    if actual > expected {
+        return Err(NetworkError::TooManyWeights { expected, actual });
+    }
+
+    Ok(expected - actual)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], FormatError> {
+        if self.bytes.len() < N {
+            return Err(FormatError::UnexpectedEof);
+        }
+
+        let (head, tail) = self.bytes.split_at(N);
+        self.bytes = tail;
+
+        Ok(head.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, FormatError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, FormatError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, FormatError> {
+        Ok(f32::from_le_bytes(self.take()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, FormatError> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    // a weight stored with `scalar` bytes
+    fn weight<F: Float>(&mut self, scalar: u8) -> Result<F, FormatError> {
+        if scalar == f64::BYTES {
+            Ok(F::from_f64(self.f64()?))
+        } else {
+            Ok(F::from_f32(self.f32()?))
+        }
+    }
+}
+
+// `f32` shorthands, see `Network::decode_json` and `Network::decode_bytes`
+// for other scalar types
+impl Network {
+    pub fn from_json(json: &str) -> Result<Self, FormatError> {
+        Self::decode_json(json)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        Self::decode_bytes(bytes)
+    }
+}
+
+impl<F: Float> Network<F> {
+    // NaN and infinite weights cannot be stored as JSON,
+    // use `to_bytes` for those
+    pub fn to_json(&self) -> Result<String, FormatError> {
+        Ok(serde_json::to_string_pretty(&NetworkDocument::new(self)?)?)
+    }
+
+    pub fn decode_json(json: &str) -> Result<Self, FormatError> {
+        // check the version before the layout,
+        // so that newer files get a meaningful error
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let version = value
+            .get("version")
+            .and_then(|version| version.as_u64())
+            .unwrap_or(0) as u32;
+
+        if !(1..=FORMAT_VERSION).contains(&version) {
+            return Err(FormatError::UnsupportedVersion(version));
+        }
+
+        serde_json::from_value::<NetworkDocument<F>>(value)?
+            .into_network()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, FormatError> {
+        let document = NetworkDocument::new(self)?;
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&document.version.to_le_bytes());
+        bytes.push(F::BYTES);
+        bytes.extend_from_slice(&(document.inputs as u32).to_le_bytes());
+        bytes.extend_from_slice(&(document.layers.len() as u32).to_le_bytes());
+
+        for layer in &document.layers {
+            let (tag, param) = layer.activation.tag();
+
+            bytes.extend_from_slice(&(layer.neurons.len() as u32).to_le_bytes());
+            bytes.push(tag);
+            bytes.extend_from_slice(&param.to_le_bytes());
+            let mut flags = 0;
+
+            if layer.recurrent {
+                flags |= RECURRENT;
+            }
+
+            if !layer.skips.is_empty() {
+                flags |= SKIPS;
+            }
+
+            bytes.push(flags);
+
+            if !layer.skips.is_empty() {
+                bytes.extend_from_slice(&(layer.skips.len() as u32).to_le_bytes());
+
+                for &skip in &layer.skips {
+                    bytes.extend_from_slice(&(skip as u32).to_le_bytes());
+                }
+            }
+
+            for neuron in &layer.neurons {
+                let weights = std::iter::once(&neuron.bias)
+                    .chain(&neuron.weights)
+                    .chain(&neuron.recurrent_weights);
+
+                for &weight in weights {
+                    if F::BYTES == f64::BYTES {
+                        bytes.extend_from_slice(&weight.to_f64().to_le_bytes());
+                    } else {
+                        bytes.extend_from_slice(&weight.to_f32().to_le_bytes());
+                    }
+                }
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    pub fn decode_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        let mut reader = Reader { bytes };
+
+        if &reader.take::<4>()? != MAGIC {
+            return Err(FormatError::InvalidMagic);
+        }
+
+        let version = reader.u32()?;
+
+        if !(1..=FORMAT_VERSION).contains(&version) {
+            return Err(FormatError::UnsupportedVersion(version));
+        }
+
+        let scalar = if version >= 3 { reader.u8()? } else { f32::BYTES };
+
+        if scalar != f32::BYTES && scalar != f64::BYTES {
+            return Err(FormatError::UnsupportedScalar(scalar));
+        }
+
+        let inputs = reader.u32()? as usize;
+        // only `neurons` matters here, for counting layer inputs
+        let mut topology = vec![LayerTopology {
+            neurons: inputs,
+            ..Default::default()
+        }];
+        let mut layers = Vec::new();
+
+        for _ in 0..reader.u32()? {
+            let neurons = reader.u32()? as usize;
+            let tag = reader.u8()?;
+            let activation = ActivationDocument::from_tag(tag, reader.f32()?)?;
+            let flags = if version >= 2 { reader.u8()? } else { 0 };
+            let recurrent = flags & RECURRENT != 0;
+            let recurrent_inputs = if recurrent { neurons } else { 0 };
+
+            let skips = if flags & SKIPS != 0 {
+                (0..reader.u32()?)
+                    .map(|_| Ok(reader.u32()? as usize))
+                    .collect::<Result<Vec<_>, FormatError>>()?
+            } else {
+                Vec::new()
+            };
+
+            let previous = skipped_inputs(&topology, topology.len(), &skips)?;
+
+            let neurons = (0..neurons)
+                .map(|_| {
+                    Ok(NeuronDocument {
+                        bias: reader.weight(scalar)?,
+                        weights: (0..previous)
+                            .map(|_| reader.weight(scalar))
+                            .collect::<Result<_, _>>()?,
+                        recurrent_weights: (0..recurrent_inputs)
+                            .map(|_| reader.weight(scalar))
+                            .collect::<Result<_, _>>()?,
+                    })
+                })
+                .collect::<Result<Vec<_>, FormatError>>()?;
+
+            topology.push(LayerTopology {
+                neurons: neurons.len(),
+                ..Default::default()
+            });
+            layers.push(LayerDocument { activation, recurrent, skips, neurons });
+        }
+
+        if !reader.bytes.is_empty() {
+            return Err(FormatError::TrailingData);
+        }
+
+        NetworkDocument { version, inputs, layers }
+            .into_network()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::builder(4)
+            .layer(3, Activation::LeakyReLU(0.05))
+            .layer(2, Activation::Tanh)
+            .build_random(&mut rng)
+            .unwrap()
+    }
+
+    fn recurrent_network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::builder(3)
+            .recurrent_layer(4, Activation::Tanh)
+            .layer(2, Activation::Identity)
+            .build_random(&mut rng)
+            .unwrap()
+    }
+
+    fn assert_same_network(actual: &Network, expected: &Network) {
+        let actual_bits: Vec<_> = actual
+            .weights()
+            .iter()
+            .map(|weight| weight.to_bits())
+            .collect();
+        let expected_bits: Vec<_> = expected
+            .weights()
+            .iter()
+            .map(|weight| weight.to_bits())
+            .collect();
+
+        assert_eq!(actual_bits, expected_bits);
+        assert_eq!(
+            format!("{:?}", actual.topology()),
+            format!("{:?}", expected.topology())
+        );
+    }
+
+    mod json {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let network = network();
+            let json = network
+                .to_json()
+                .unwrap();
+
+            assert_same_network(&Network::from_json(&json).unwrap(), &network);
+        }
+
+        #[test]
+        fn recurrent_round_trip() {
+            let network = recurrent_network();
+            let json = network
+                .to_json()
+                .unwrap();
+
+            assert!(json.contains("recurrent_weights"));
+            assert_same_network(&Network::from_json(&json).unwrap(), &network);
+        }
+
+        #[test]
+        fn version_1() {
+            let json = r#"{
+                "version": 1,
+                "inputs": 1,
+                "layers": [{
+                    "activation": { "type": "Tanh" },
+                    "neurons": [{ "bias": 0.5, "weights": [-0.25] }]
+                }]
+            }"#;
+
+            let network = Network::from_json(json).unwrap();
+
+            assert_eq!(network.weights(), vec![0.5, -0.25]);
+            assert!(!network.topology()[1].recurrent);
+        }
+
+        #[test]
+        fn unsupported_version() {
+            let json = network()
+                .to_json()
+                .unwrap()
+                .replace("\"version\": 4", "\"version\": 99");
+
+            assert!(matches!(
+                Network::from_json(&json),
+                Err(FormatError::UnsupportedVersion(99))
+            ));
+        }
+
+        #[test]
+        fn short_neuron() {
+            let json = r#"{
+                "version": 1,
+                "inputs": 2,
+                "layers": [{
+                    "activation": { "type": "ReLU" },
+                    "neurons": [{ "bias": 0.5, "weights": [0.1] }]
+                }]
+            }"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(FormatError::Network(NetworkError::NotEnoughWeights {
+                    layer: 1,
+                    neuron: 0,
+                    short: 1,
+                    missing: 1
+                }))
+            ));
+        }
+
+        #[test]
+        fn short_neurons() {
+            let json = r#"{
+                "version": 1,
+                "inputs": 2,
+                "layers": [{
+                    "activation": { "type": "ReLU" },
+                    "neurons": [
+                        { "bias": 0.5, "weights": [0.1, 0.2] },
+                        { "bias": 0.5, "weights": [0.1] }
+                    ]
+                }, {
+                    "activation": { "type": "ReLU" },
+                    "neurons": [{ "bias": 0.5, "weights": [] }]
+                }]
+            }"#;
+
+            // the first short neuron, and all that's missing
+            assert!(matches!(
+                Network::from_json(json),
+                Err(FormatError::Network(NetworkError::NotEnoughWeights {
+                    layer: 1,
+                    neuron: 1,
+                    short: 1,
+                    missing: 3
+                }))
+            ));
+        }
+    }
+
+    mod bytes {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let network = network();
+            let bytes = network
+                .to_bytes()
+                .unwrap();
+
+            // header + 2 layer headers + 23 weights
+            assert_eq!(bytes.len(), 17 + 2 * 10 + 23 * 4);
+            assert_same_network(&Network::from_bytes(&bytes).unwrap(), &network);
+        }
+
+        #[test]
+        fn recurrent_round_trip() {
+            let network = recurrent_network();
+            let bytes = network
+                .to_bytes()
+                .unwrap();
+
+            assert_same_network(&Network::from_bytes(&bytes).unwrap(), &network);
+        }
+
+        #[test]
+        fn version_1() {
+            let mut bytes = Vec::new();
+
+            bytes.extend_from_slice(b"SLNN");
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+            bytes.push(3);
+            bytes.extend_from_slice(&0.0f32.to_le_bytes());
+            bytes.extend_from_slice(&0.5f32.to_le_bytes());
+            bytes.extend_from_slice(&(-0.25f32).to_le_bytes());
+
+            let network = Network::from_bytes(&bytes).unwrap();
+
+            assert_eq!(network.weights(), vec![0.5, -0.25]);
+            assert!(!network.topology()[1].recurrent);
+        }
+
+        #[test]
+        fn invalid_magic() {
+            assert!(matches!(
+                Network::from_bytes(b"JSON{}"),
+                Err(FormatError::InvalidMagic)
+            ));
+        }
+
+        #[test]
+        fn truncated() {
+            let bytes = network()
+                .to_bytes()
+                .unwrap();
+
+            assert!(matches!(
+                Network::from_bytes(&bytes[..bytes.len() - 1]),
+                Err(FormatError::UnexpectedEof)
+            ));
+        }
+
+        #[test]
+        fn trailing_data() {
+            let mut bytes = network()
+                .to_bytes()
+                .unwrap();
+            bytes.push(0);
+
+            assert!(matches!(
+                Network::from_bytes(&bytes),
+                Err(FormatError::TrailingData)
+            ));
+        }
+    }
+
+    mod skips {
+        use super::*;
+
+        fn skip_network() -> Network {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            Network::builder(3)
+                .layer(4, Activation::ReLU)
+                .layer(2, Activation::Tanh)
+                .skip_from(0)
+                .build_random(&mut rng)
+                .unwrap()
+        }
+
+        #[test]
+        fn json_round_trip() {
+            let network = skip_network();
+            let json = network
+                .to_json()
+                .unwrap();
+
+            assert!(json.contains("skips"));
+            assert_same_network(&Network::from_json(&json).unwrap(), &network);
+        }
+
+        #[test]
+        fn bytes_round_trip() {
+            let network = skip_network();
+            let bytes = network
+                .to_bytes()
+                .unwrap();
+
+            assert_same_network(&Network::from_bytes(&bytes).unwrap(), &network);
+        }
+
+        #[test]
+        fn invalid() {
+            let json = r#"{
+                "version": 4,
+                "inputs": 1,
+                "layers": [{
+                    "activation": { "type": "ReLU" },
+                    "skips": [0],
+                    "neurons": [{ "bias": 0.5, "weights": [0.1, 0.2] }]
+                }]
+            }"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(FormatError::Network(NetworkError::InvalidSkip { layer: 1, from: 0 }))
+            ));
+        }
+    }
+
+    mod scalar {
+        use super::*;
+
+        fn f64_network() -> Network<f64> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            NetworkBuilder::new(4)
+                .layer(3, Activation::Sigmoid)
+                .layer(2, Activation::Tanh)
+                .scalar::<f64>()
+                .build_random(&mut rng)
+                .unwrap()
+        }
+
+        #[test]
+        fn json_round_trip() {
+            let network = f64_network();
+            let json = network
+                .to_json()
+                .unwrap();
+
+            let loaded = Network::<f64>::decode_json(&json).unwrap();
+
+            assert_eq!(loaded.weights(), network.weights());
+        }
+
+        #[test]
+        fn bytes_round_trip() {
+            let network = f64_network();
+            let bytes = network
+                .to_bytes()
+                .unwrap();
+
+            // header + 2 layer headers + 23 weights
+            assert_eq!(bytes.len(), 17 + 2 * 10 + 23 * 8);
+
+            let loaded = Network::<f64>::decode_bytes(&bytes).unwrap();
+
+            assert_eq!(loaded.weights(), network.weights());
+        }
+
+        #[test]
+        fn f32_into_f64() {
+            let network = network();
+            let bytes = network
+                .to_bytes()
+                .unwrap();
+
+            let loaded = Network::<f64>::decode_bytes(&bytes).unwrap();
+            let expected: Vec<f64> = network
+                .weights()
+                .into_iter()
+                .map(f64::from)
+                .collect();
+
+            assert_eq!(loaded.weights(), expected);
+        }
+
+        #[test]
+        fn unsupported_scalar() {
+            let mut bytes = network()
+                .to_bytes()
+                .unwrap();
+            bytes[8] = 2;
+
+            assert!(matches!(
+                Network::from_bytes(&bytes),
+                Err(FormatError::UnsupportedScalar(2))
+            ));
+        }
+    }
+
+    #[test]
+    fn custom_activation() {
+        let network = Network::builder(1)
+            .layer(1, Activation::Custom(|x| x))
+            .build_from_weights(vec![0.0, 1.0])
+            .unwrap();
+
+        assert!(matches!(
+            network.to_json(),
+            Err(FormatError::CustomActivation { layer: 1 })
+        ));
+        assert!(matches!(
+            network.to_bytes(),
+            Err(FormatError::CustomActivation { layer: 1 })
+        ));
+    }
+}
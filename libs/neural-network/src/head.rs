@@ -0,0 +1,188 @@
+use rand::{Rng, RngCore};
+
+use super::float::Float;
+
+// How the outputs of a network's last layer are turned into a decision
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputHead {
+    // outputs are used as they are
+    #[default]
+    Continuous,
+    // outputs are logits, turned into a probability per action
+    Softmax,
+    // the action with the highest logit
+    Argmax,
+    // an action drawn from the softmax of the logits
+    Categorical,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decision<F = f32> {
+    Continuous(Vec<F>),
+    Probabilities(Vec<F>),
+    // index of the chosen output
+    Action(usize),
+}
+
+impl OutputHead {
+    // whether the network's outputs are one logit per action
+    pub fn is_categorical(&self) -> bool {
+        *self != Self::Continuous
+    }
+
+    // only `Categorical` touches `rng`
+    pub fn decide<F: Float>(&self, outputs: Vec<F>, rng: &mut dyn RngCore) -> Decision<F> {
+        match self {
+            Self::Continuous => Decision::Continuous(outputs),
+            Self::Softmax => Decision::Probabilities(softmax(&outputs)),
+            Self::Argmax => Decision::Action(argmax(&outputs)),
+            Self::Categorical => Decision::Action(sample(&softmax(&outputs), rng)),
+        }
+    }
+}
+
+pub fn softmax<F: Float>(logits: &[F]) -> Vec<F> {
+    // shifting by the maximum keeps `exp` from overflowing
+    let max = logits
+        .iter()
+        .fold(F::neg_infinity(), |max, &logit| max.max(logit));
+
+    let exps: Vec<F> = logits
+        .iter()
+        .map(|&logit| (logit - max).exp())
+        .collect();
+
+    let sum = exps.iter().cloned().sum::<F>();
+
+    exps.into_iter()
+        .map(|exp| exp / sum)
+        .collect()
+}
+
+// first index of the largest value, NaNs are never picked
+pub fn argmax<F: Float>(values: &[F]) -> usize {
+    assert!(!values.is_empty());
+
+    let mut best = 0;
+
+    for (idx, &value) in values.iter().enumerate() {
+        if value > values[best] || values[best].is_nan() {
+            best = idx;
+        }
+    }
+
+    best
+}
+
+// draws an index with the given probabilities, which must sum up to 1
+fn sample<F: Float>(probabilities: &[F], rng: &mut dyn RngCore) -> usize {
+    assert!(!probabilities.is_empty());
+
+    let mut roll = rng.gen::<f64>();
+
+    for (idx, probability) in probabilities.iter().enumerate() {
+        roll -= Float::to_f64(*probability);
+
+        if roll < 0.0 {
+            return idx;
+        }
+    }
+
+    // rounding can leave a tiny bit of `roll` over
+    probabilities.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    mod softmax {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let probabilities = softmax(&[1.0, 2.0, 3.0]);
+            let sum: f32 = probabilities.iter().sum();
+
+            assert_relative_eq!(sum, 1.0);
+            assert_relative_eq!(probabilities[0], 0.09003057);
+            assert_relative_eq!(probabilities[1], 0.24472847);
+            assert_relative_eq!(probabilities[2], 0.66524096);
+        }
+
+        #[test]
+        fn large_logits() {
+            let probabilities = softmax(&[1000.0_f32, 1000.0]);
+
+            assert_relative_eq!(probabilities[0], 0.5);
+            assert_relative_eq!(probabilities[1], 0.5);
+        }
+    }
+
+    mod argmax {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_eq!(argmax(&[0.1, 0.7, -0.2]), 1);
+            assert_eq!(argmax(&[0.5, 0.5]), 0);
+            assert_eq!(argmax(&[f32::NAN, -1.0]), 1);
+        }
+    }
+
+    mod decide {
+        use super::*;
+
+        #[test]
+        fn continuous() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            assert_eq!(
+                OutputHead::Continuous.decide(vec![0.25, -0.5], &mut rng),
+                Decision::Continuous(vec![0.25, -0.5])
+            );
+        }
+
+        #[test]
+        fn softmax() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            assert_eq!(
+                OutputHead::Softmax.decide(vec![0.0, 0.0], &mut rng),
+                Decision::Probabilities(vec![0.5, 0.5])
+            );
+        }
+
+        #[test]
+        fn argmax() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            assert_eq!(
+                OutputHead::Argmax.decide(vec![0.1, -0.3, 0.4], &mut rng),
+                Decision::Action(2)
+            );
+        }
+
+        #[test]
+        fn categorical() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let logits = vec![0.0_f32, 1.0, 2.0];
+            let expected = super::softmax(&logits);
+            let mut counts = [0; 3];
+
+            for _ in 0..10_000 {
+                match OutputHead::Categorical.decide(logits.clone(), &mut rng) {
+                    Decision::Action(action) => counts[action] += 1,
+                    decision => panic!("unexpected decision: {:?}", decision),
+                }
+            }
+
+            for (count, probability) in counts.iter().zip(expected) {
+                assert_relative_eq!(*count as f32 / 10_000.0, probability, epsilon = 0.02);
+            }
+        }
+    }
+}
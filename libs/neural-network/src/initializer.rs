@@ -0,0 +1,209 @@
+use rand::{Rng, RngCore};
+
+use super::float::Float;
+
+#[derive(Clone, Copy, Debug)]
+pub enum WeightInit {
+    Uniform { low: f32, high: f32 },
+    Gaussian { mean: f32, std_dev: f32 },
+    // Glorot: uniform in +-sqrt(6 / (fan_in + fan_out))
+    Xavier,
+    // Kaiming: gaussian with std_dev = sqrt(2 / fan_in), suits ReLU
+    He,
+    Zeros,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum BiasInit {
+    // drawn the same way as the weights
+    Weights,
+    Constant(f32),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Initializer {
+    pub weights: WeightInit,
+    pub bias: BiasInit,
+}
+
+impl Initializer {
+    pub fn uniform(low: f32, high: f32) -> Self {
+        assert!(low <= high);
+
+        Self::new(WeightInit::Uniform { low, high })
+    }
+
+    pub fn gaussian(mean: f32, std_dev: f32) -> Self {
+        assert!(std_dev >= 0.0);
+
+        Self::new(WeightInit::Gaussian { mean, std_dev })
+    }
+
+    pub fn xavier() -> Self {
+        Self::new(WeightInit::Xavier)
+    }
+
+    pub fn he() -> Self {
+        Self::new(WeightInit::He)
+    }
+
+    pub fn zeros() -> Self {
+        Self::new(WeightInit::Zeros)
+    }
+
+    pub fn with_constant_bias(self, bias: f32) -> Self {
+        Self {
+            bias: BiasInit::Constant(bias),
+            ..self
+        }
+    }
+
+    fn new(weights: WeightInit) -> Self {
+        Self {
+            weights,
+            bias: BiasInit::Weights,
+        }
+    }
+
+    pub(crate) fn samplers<F: Float>(&self, fan_in: usize, fan_out: usize) -> (Sampler<F>, Sampler<F>) {
+        let weights = match self.weights {
+            WeightInit::Uniform { low, high } => Sampler::uniform(low, high),
+            WeightInit::Gaussian { mean, std_dev } => Sampler::gaussian(mean, std_dev),
+            WeightInit::Xavier => {
+                let limit = (6.0 / (fan_in + fan_out).max(1) as f32).sqrt();
+
+                Sampler::uniform(-limit, limit)
+            }
+            WeightInit::He => {
+                Sampler::gaussian(0.0, (2.0 / fan_in.max(1) as f32).sqrt())
+            }
+            WeightInit::Zeros => Sampler::Constant(F::zero()),
+        };
+
+        let bias = match self.bias {
+            BiasInit::Weights => weights.clone(),
+            BiasInit::Constant(bias) => Sampler::Constant(F::from_f32(bias)),
+        };
+
+        (weights, bias)
+    }
+}
+
+// uniform(-1, 1) for both weights and biases
+impl Default for Initializer {
+    fn default() -> Self {
+        Self::uniform(-1.0, 1.0)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Sampler<F> {
+    Uniform(F, F),
+    Gaussian { mean: F, std_dev: F },
+    // doesn't touch the rng
+    Constant(F),
+}
+
+impl<F: Float> Sampler<F> {
+    fn uniform(low: f32, high: f32) -> Self {
+        Self::Uniform(F::from_f32(low), F::from_f32(high))
+    }
+
+    fn gaussian(mean: f32, std_dev: f32) -> Self {
+        Self::Gaussian {
+            mean: F::from_f32(mean),
+            std_dev: F::from_f32(std_dev),
+        }
+    }
+
+    pub(crate) fn sample(&self, rng: &mut dyn RngCore) -> F {
+        match *self {
+            Self::Uniform(low, high) => rng.gen_range(low..=high),
+            // same as `rand_distr::Normal`
+            Self::Gaussian { mean, std_dev } => mean + std_dev * F::standard_normal(rng),
+            Self::Constant(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    fn sample(initializer: Initializer, fan_in: usize, fan_out: usize) -> (Vec<f32>, Vec<f32>) {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let (weights, bias) = initializer.samplers::<f32>(fan_in, fan_out);
+
+        let weights = (0..1000)
+            .map(|_| weights.sample(&mut rng))
+            .collect();
+        let biases = (0..1000)
+            .map(|_| bias.sample(&mut rng))
+            .collect();
+
+        (weights, biases)
+    }
+
+    fn mean(values: &[f32]) -> f32 {
+        values.iter().sum::<f32>() / values.len() as f32
+    }
+
+    fn std_dev(values: &[f32]) -> f32 {
+        let mean = mean(values);
+
+        (values.iter().map(|value| (value - mean).powi(2)).sum::<f32>()
+            / values.len() as f32)
+            .sqrt()
+    }
+
+    #[test]
+    fn uniform() {
+        let (weights, _) = sample(Initializer::uniform(0.5, 0.75), 4, 4);
+
+        assert!(weights.iter().all(|&weight| (0.5..=0.75).contains(&weight)));
+    }
+
+    #[test]
+    fn gaussian() {
+        let (weights, _) = sample(Initializer::gaussian(2.0, 0.5), 4, 4);
+
+        assert_relative_eq!(mean(&weights), 2.0, epsilon = 0.05);
+        assert_relative_eq!(std_dev(&weights), 0.5, epsilon = 0.05);
+    }
+
+    #[test]
+    fn xavier() {
+        let (weights, _) = sample(Initializer::xavier(), 10, 14);
+        let limit = 0.5;
+
+        assert!(weights.iter().all(|&weight| weight.abs() <= limit));
+        assert!(weights.iter().any(|&weight| weight.abs() > 0.9 * limit));
+    }
+
+    #[test]
+    fn he() {
+        let (weights, _) = sample(Initializer::he(), 8, 2);
+
+        assert_relative_eq!(mean(&weights), 0.0, epsilon = 0.05);
+        assert_relative_eq!(std_dev(&weights), 0.5, epsilon = 0.05);
+    }
+
+    #[test]
+    fn zeros() {
+        let (weights, biases) = sample(Initializer::zeros(), 4, 4);
+
+        assert!(weights.iter().all(|&weight| weight == 0.0));
+        assert!(biases.iter().all(|&bias| bias == 0.0));
+    }
+
+    #[test]
+    fn constant_bias() {
+        let (weights, biases) = sample(Initializer::he().with_constant_bias(0.1), 4, 4);
+
+        assert!(weights.iter().any(|&weight| weight != 0.1));
+        assert!(biases.iter().all(|&bias| bias == 0.1));
+    }
+}
@@ -1,41 +1,155 @@
 use super::neuron;
+use super::activation::Activation;
+use super::float::Float;
+use super::initializer::Initializer;
+use super::trace::LayerTrace;
 
-pub struct Layer {
-    pub(crate) neurons: Vec<neuron::Neuron>,
+pub struct Layer<F = f32> {
+    pub(crate) neurons: Vec<neuron::Neuron<F>>,
+    pub(crate) activation: Activation,
+    // Elman-style layer: each neuron also sees the layer's outputs
+    // from the previous step, weighted by the tail of its `weights`
+    pub(crate) recurrent: bool,
+    // see `LayerTopology::skips`
+    pub(crate) skips: Vec<usize>,
 }
 
-impl Layer {
+impl<F: Float> Layer<F> {
     pub(crate) fn randomize(
         input_neurons: usize,
         output_neurons: usize,
+        activation: Activation,
+        recurrent: bool,
+        initializer: &Initializer,
         rng: &mut dyn rand::RngCore
     ) -> Self {
+        let synapses = Self::synapses(input_neurons, output_neurons, recurrent);
+        let (weights, bias) = initializer.samplers(synapses, output_neurons);
+
         let neurons = (0..output_neurons)
-        .map(|_| neuron::Neuron::randomize(input_neurons, rng))
+        .map(|_| neuron::Neuron::randomize(synapses, &weights, &bias, rng))
         .collect();
         
-        Self { neurons }
+        Self { neurons, activation, recurrent, skips: Vec::new() }
     }
 
-    pub fn from_weights(
+    pub(crate) fn from_weights(
         input_size: usize,
         output_size: usize,
-        weights: &mut dyn Iterator<Item = f32>
+        activation: Activation,
+        recurrent: bool,
+        weights: &mut dyn Iterator<Item = F>
     ) -> Self {
+        let synapses = Self::synapses(input_size, output_size, recurrent);
+
         let neurons = (0..output_size)
-            .map(|_| neuron::Neuron::from_weights(input_size, weights))
+            .map(|_| neuron::Neuron::from_weights(synapses, weights))
             .collect();
 
         Self {
-            neurons
+            neurons,
+            activation,
+            recurrent,
+            skips: Vec::new()
         }
     }
 
-    pub(crate) fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.neurons
+    pub(crate) fn with_skips(mut self, skips: &[usize]) -> Self {
+        self.skips = skips.to_vec();
+        self
+    }
+
+    // The layer's input: outputs of the previous layer, followed by
+    // the outputs of each skipped-from layer; `outputs[0]` holds
+    // the network's inputs
+    pub(crate) fn gather(&self, outputs: &[Vec<F>]) -> Vec<F> {
+        let mut inputs = outputs[outputs.len() - 1].clone();
+
+        for &skip in &self.skips {
+            inputs.extend_from_slice(&outputs[skip]);
+        }
+
+        inputs
+    }
+
+    // number of incoming weights per neuron, bias excluded
+    pub(crate) fn synapses(inputs: usize, outputs: usize, recurrent: bool) -> usize {
+        if recurrent {
+            inputs + outputs
+        } else {
+            inputs
+        }
+    }
+
+    pub(crate) fn inputs(&self) -> usize {
+        let synapses = self.neurons[0].weights.len();
+
+        if self.recurrent {
+            synapses - self.neurons.len()
+        } else {
+            synapses
+        }
+    }
+
+    // state of a layer that hasn't seen any input yet
+    pub(crate) fn initial_state(&self) -> Vec<F> {
+        if self.recurrent {
+            vec![F::zero(); self.neurons.len()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub(crate) fn propagate(&self, inputs: Vec<F>) -> Vec<F> {
+        self.propagate_with_state(inputs, &mut self.initial_state())
+    }
+
+    pub(crate) fn propagate_with_state(
+        &self,
+        mut inputs: Vec<F>,
+        state: &mut Vec<F>
+    ) -> Vec<F> {
+        if self.recurrent {
+            inputs.extend_from_slice(state);
+        }
+
+        let outputs: Vec<F> = self.neurons
+        .iter()
+        .map(|neuron| neuron.propagate(&inputs, self.activation))
+        .collect();
+
+        if self.recurrent {
+            state.clone_from(&outputs);
+        }
+
+        outputs
+    }
+
+    // same as `propagate_with_state`, but keeps the pre-activation sums
+    pub(crate) fn trace_with_state(
+        &self,
+        mut inputs: Vec<F>,
+        state: &mut Vec<F>
+    ) -> LayerTrace<F> {
+        if self.recurrent {
+            inputs.extend_from_slice(state);
+        }
+
+        let sums: Vec<F> = self.neurons
         .iter()
-        .map(|neuron| neuron.propagate(&inputs))
-        .collect()
+        .map(|neuron| neuron.sum(&inputs))
+        .collect();
+
+        let outputs: Vec<F> = sums
+        .iter()
+        .map(|&sum| self.activation.eval(sum))
+        .collect();
+
+        if self.recurrent {
+            state.clone_from(&outputs);
+        }
+
+        LayerTrace { sums, outputs }
     }
 }
 
@@ -52,7 +166,7 @@ mod tests {
         #[test]
         fn randomize_test() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let layer = Layer::randomize(3, 2, &mut rng);
+            let layer: Layer = Layer::randomize(3, 2, Activation::ReLU, false, &Initializer::default(), &mut rng);
             
             assert_eq!(layer.neurons.len(), 2);
             
@@ -79,7 +193,7 @@ mod tests {
         
         #[test]
         fn propagate_test() {
-            let layer = Layer {
+            let layer: Layer = Layer {
                 neurons: vec![
                 neuron::Neuron {
                     bias: 0.2,
@@ -89,7 +203,10 @@ mod tests {
                     bias: -0.5,
                     weights: vec![-0.1, 0.75]
                 },
-                ]
+                ],
+                activation: Activation::ReLU,
+                recurrent: false,
+                skips: Vec::new()
             };
             
             let result = layer
@@ -100,5 +217,87 @@ mod tests {
             0.0 
             ].as_ref());
         }
+
+        #[test]
+        fn propagate_with_activation_test() {
+            let layer: Layer = Layer {
+                neurons: vec![
+                neuron::Neuron {
+                    bias: 0.2,
+                    weights: vec![-0.1, 0.3]
+                },
+                neuron::Neuron {
+                    bias: -0.5,
+                    weights: vec![-0.1, 0.75]
+                },
+                ],
+                activation: Activation::LeakyReLU(0.01),
+                recurrent: false,
+                skips: Vec::new()
+            };
+            
+            let result = layer
+            .propagate(vec![0.5, -0.4]);
+            
+            assert_relative_eq!(result.as_ref(),[
+            (0.5 * (-0.1)) + (-0.4 * 0.3) + 0.2,
+            0.01 * ((0.5 * (-0.1)) + (-0.4 * 0.75) - 0.5)
+            ].as_ref());
+        }
+    
+        #[test]
+        fn randomize_recurrent_test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let layer: Layer = Layer::randomize(3, 2, Activation::ReLU, true, &Initializer::default(), &mut rng);
+
+            assert_eq!(layer.inputs(), 3);
+            assert_eq!(layer.neurons[0].weights.len(), 5);
+            assert_eq!(layer.neurons[1].weights.len(), 5);
+        }
+
+        #[test]
+        fn propagate_with_state_test() {
+            let layer: Layer = Layer {
+                neurons: vec![
+                neuron::Neuron {
+                    bias: 0.1,
+                    // input, self, other
+                    weights: vec![0.5, 0.2, -0.4]
+                },
+                neuron::Neuron {
+                    bias: 0.0,
+                    weights: vec![-0.3, 0.6, 0.8]
+                },
+                ],
+                activation: Activation::Identity,
+                recurrent: true,
+                skips: Vec::new()
+            };
+
+            let mut state = layer.initial_state();
+
+            let first = layer
+            .propagate_with_state(vec![1.0], &mut state);
+            let first_expected: Vec<f32> = vec![0.5 + 0.1, -0.3];
+
+            assert_relative_eq!(first.as_slice(), first_expected.as_slice());
+            assert_relative_eq!(state.as_slice(), first_expected.as_slice());
+
+            let second = layer
+            .propagate_with_state(vec![1.0], &mut state);
+
+            let second_expected = [
+            0.5 + (0.2 * first[0]) + (-0.4 * first[1]) + 0.1,
+            -0.3 + (0.6 * first[0]) + (0.8 * first[1])
+            ];
+
+            assert_relative_eq!(second.as_slice(), second_expected.as_ref());
+
+            // stateless propagation always starts from scratch
+            let stateless = layer
+            .propagate(vec![1.0]);
+
+            assert_relative_eq!(stateless.as_slice(), first_expected.as_slice());
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,64 +1,182 @@
 mod neuron;
 mod layer;
-
-//TODO: Implement a better interface for creating networks
+mod activation;
+mod builder;
+pub mod ctrnn;
+mod distance;
+mod error;
+mod export;
+mod float;
+mod format;
+mod head;
+mod initializer;
+mod matrix;
+pub mod neat;
+mod plasticity;
+mod prune;
+mod quantized;
+mod saliency;
+mod trace;
+mod training;
+
+pub use activation::Activation;
+pub use builder::NetworkBuilder;
+pub use distance::Distance;
+pub use error::{FormatError, NetworkError};
+pub use export::Labels;
+pub use float::Float;
+pub use format::FORMAT_VERSION;
+pub use head::{argmax, softmax, Decision, OutputHead};
+pub use initializer::{BiasInit, Initializer, WeightInit};
+pub use matrix::{MatrixNetwork, Scratch};
+pub use plasticity::{HebbianRule, Plasticity};
+pub use prune::{Histogram, NetworkStats, HISTOGRAM_BINS};
+pub use quantized::{Quantization, QuantizedNetwork, QuantizedScratch};
+pub use saliency::Saliency;
+pub use trace::{LayerTrace, Trace};
+pub use training::{mse, Optimizer, Sample, Trainer};
+
+#[derive(Clone, Debug, Default)]
 pub struct LayerTopology {
     pub neurons: usize,
+    // ignored for the input layer
+    pub activation: Activation,
+    // feeds the layer's previous outputs back into it,
+    // see `Network::propagate_with_state`
+    pub recurrent: bool,
+    // Earlier layers (0 = input layer, never the previous one) whose
+    // outputs are fed into this layer too, as skip connections.
+    //
+    // A neuron's weights are laid out as: bias, one weight per output
+    // of the previous layer, one weight per output of each skipped-from
+    // layer (in `skips` order), then the recurrent weights, if any
+    pub skips: Vec<usize>,
+}
+
+// Per-network memory of recurrent layers, kept outside of `Network`
+// so that a single network can be shared by many stateful users
+#[derive(Clone, Debug)]
+pub struct NetworkState<F = f32> {
+    layers: Vec<Vec<F>>,
+}
+
+impl<F: Float> NetworkState<F> {
+    pub fn reset(&mut self) {
+        for layer in &mut self.layers {
+            layer.fill(F::zero());
+        }
+    }
 }
 
-pub struct Network {
-    layers: Vec<layer::Layer>,
+pub struct Network<F = f32> {
+    layers: Vec<layer::Layer<F>>,
 }
 
+// Constructors that cannot tell the scalar type from their arguments
+// are `f32`-only; other scalars go through `NetworkBuilder::scalar`
 impl Network {
-    pub fn randomize(layers: &[LayerTopology], rng: &mut dyn rand::RngCore) -> Self {
-        assert!(layers.len() > 1);
+    pub fn builder(inputs: usize) -> NetworkBuilder {
+        NetworkBuilder::new(inputs)
+    }
 
-        let layers = layers
-            .windows(2)
-            .map(|layers| {
-                layer::Layer::randomize(layers[0].neurons, layers[1].neurons, rng)
-            })
-            .collect();
+    pub fn randomize(layers: &[LayerTopology], rng: &mut dyn rand::RngCore) -> Self {
+        Self::randomize_with(layers, Initializer::default(), rng)
+    }
 
-        Self { layers }
+    pub fn randomize_with(
+        layers: &[LayerTopology],
+        initializer: Initializer,
+        rng: &mut dyn rand::RngCore
+    ) -> Self {
+        NetworkBuilder::from_topology(layers)
+            .initializer(initializer)
+            .build_random(rng)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 
     pub fn from_weights(
         layers: &[LayerTopology],
         weights: impl IntoIterator<Item = f32>
     ) -> Self {
-        assert!(layers.len() > 1);
-
-        let mut weights = weights.into_iter();
-
-        let layers = layers
-            .windows(2)
-            .map(|layers| {
-                layer::Layer::from_weights(
-                    layers[0].neurons,
-                    layers[1].neurons,
-                    &mut weights
-                )
-            })
-            .collect();
+        Self::try_from_weights(layers, weights)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        if weights.next().is_some() {
-            panic!("too many weights!");
+    pub fn try_from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = f32>
+    ) -> Result<Self, NetworkError> {
+        NetworkBuilder::from_topology(layers)
+            .build_from_weights(weights)
+    }
+}
+
+impl<F: Float> Network<F> {
+    pub fn inputs(&self) -> usize {
+        self.layers[0].inputs()
+    }
+
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        let input = LayerTopology {
+            neurons: self.inputs(),
+            activation: Activation::Identity,
+            recurrent: false,
+            skips: Vec::new(),
+        };
+
+        std::iter::once(input)
+            .chain(self.layers.iter().map(|layer| LayerTopology {
+                neurons: layer.neurons.len(),
+                activation: layer.activation,
+                recurrent: layer.recurrent,
+                skips: layer.skips.clone(),
+            }))
+            .collect()
+    }
+
+    pub fn state(&self) -> NetworkState<F> {
+        NetworkState {
+            layers: self.layers
+                .iter()
+                .map(|layer| layer.initial_state())
+                .collect(),
         }
+    }
+
+    // Recurrent layers see a zeroed state, i.e. the network behaves
+    // as if it had never seen any input before
+    pub fn propagate(&self, inputs: Vec<F>) -> Vec<F> {
+        // every layer's outputs, kept around for skip connections
+        let mut outputs = vec![inputs];
 
-        Self {
-            layers
+        for layer in &self.layers {
+            let inputs = layer.gather(&outputs);
+
+            outputs.push(layer.propagate(inputs));
         }
+
+        outputs.pop().unwrap()
     }
 
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.layers
-            .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+    pub fn propagate_with_state(
+        &self,
+        inputs: Vec<F>,
+        state: &mut NetworkState<F>
+    ) -> Vec<F> {
+        assert_eq!(state.layers.len(), self.layers.len());
+
+        let mut outputs = vec![inputs];
+
+        for (layer, state) in self.layers.iter().zip(&mut state.layers) {
+            let inputs = layer.gather(&outputs);
+
+            outputs.push(layer.propagate_with_state(inputs, state));
+        }
+
+        outputs.pop().unwrap()
     }
 
-    pub fn weights(&self) -> Vec<f32> {
+    pub fn weights(&self) -> Vec<F> {
         use std::iter::once;
 
         self.layers
@@ -68,6 +186,22 @@ impl Network {
             .cloned()
             .collect()
     }
+
+    // Where every neuron's genes begin within `weights`, e.g. to cross
+    // networks over without splitting neurons apart
+    pub fn neuron_offsets(&self) -> Vec<usize> {
+        let mut offset = 0;
+
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .map(|neuron| {
+                let start = offset;
+                offset += 1 + neuron.weights.len();
+                start
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -85,13 +219,16 @@ mod tests {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
             let network = Network::randomize(&[
                 LayerTopology {
-                    neurons: 1
+                    neurons: 1,
+                    ..Default::default()
                 },
                 LayerTopology {
-                    neurons: 2
+                    neurons: 2,
+                    ..Default::default()
                 },
                 LayerTopology {
-                    neurons: 1
+                    neurons: 1,
+                    ..Default::default()
                 }
             ], &mut rng);
 
@@ -160,6 +297,9 @@ mod tests {
                                 weights: vec![-0.1, /*0.18, 0.45*/]
                             }
                         ],
+                        activation: Activation::ReLU,
+                        recurrent: false,
+                        skips: Vec::new(),
                     },
                     layer::Layer {
                         neurons: vec![
@@ -168,6 +308,9 @@ mod tests {
                                 weights: vec![0.3, 0.5]
                             },
                         ],
+                        activation: Activation::ReLU,
+                        recurrent: false,
+                        skips: Vec::new(),
                     }
                 ],
             };
@@ -190,6 +333,47 @@ mod tests {
 
             assert_relative_eq!(result.as_slice(), l3.as_ref());
         }
+
+        #[test]
+        fn propagate_with_activations_test() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 2,
+                        activation: Activation::Identity,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Tanh,
+                        ..Default::default()
+                    },
+                ],
+                vec![
+                    0.1, -0.5,
+                    -0.2, 0.4,
+                    0.05, 0.3, -0.9
+                ]
+            );
+
+            let result = network
+                .propagate(vec![0.8]);
+
+            let l2: Vec<f32> = vec![
+                (-0.5 * 0.8) + 0.1,
+                (0.4 * 0.8) - 0.2
+            ];
+            let l3 = vec![
+                ((0.3 * l2[0]) + (-0.9 * l2[1]) + 0.05).tanh()
+            ];
+
+            assert!(result[0] < 0.0);
+            assert_relative_eq!(result.as_slice(), l3.as_ref());
+        }
     }
 
     mod weights {
@@ -205,7 +389,10 @@ mod tests {
                                 bias: 0.25,
                                 weights: vec![0.1, 0.5, 0.4]
                             }
-                        ]
+                        ],
+                        activation: Activation::ReLU,
+                        recurrent: false,
+                        skips: Vec::new()
                     },
                     layer::Layer {
                         neurons: vec![
@@ -213,7 +400,10 @@ mod tests {
                                 bias: 0.3,
                                 weights: vec![0.2, 0.6, 0.1]
                             }
-                        ]
+                        ],
+                        activation: Activation::ReLU,
+                        recurrent: false,
+                        skips: Vec::new()
                     }
                 ]
             };
@@ -233,6 +423,23 @@ mod tests {
         }
     }
 
+    mod neuron_offsets {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let network = Network::builder(2)
+                .recurrent_layer(2, Activation::ReLU)
+                .layer(1, Activation::Identity)
+                .skip_from(0)
+                .build_from_weights(vec![0.0; 2 * 5 + 5])
+                .unwrap();
+
+            // (bias + 2 inputs + 2 recurrent) x 2, then bias + 2 + 2 skipped
+            assert_eq!(network.neuron_offsets(), vec![0, 5, 10]);
+        }
+    }
+
     mod from_weights {
         use super::*;
 
@@ -246,10 +453,12 @@ mod tests {
             let network = Network::from_weights(
                 &[
                     LayerTopology {
-                        neurons: 3
+                        neurons: 3,
+                        ..Default::default()
                     },
                     LayerTopology {
-                        neurons: 2
+                        neurons: 2,
+                        ..Default::default()
                     },
                 ],
                 weights.clone()
@@ -263,4 +472,121 @@ mod tests {
             )
         }
     }
+
+    mod propagate_with_state {
+        use super::*;
+
+        fn network() -> Network {
+            Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Identity,
+                        recurrent: true,
+                        skips: Vec::new(),
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Identity,
+                        recurrent: false,
+                        skips: Vec::new(),
+                    },
+                ],
+                vec![
+                    // bias, input, previous output
+                    0.0, 1.0, 0.5,
+                    0.0, 2.0
+                ]
+            )
+        }
+
+        #[test]
+        fn remembers_previous_inputs() {
+            let network = network();
+            let mut state = network.state();
+
+            let outputs: Vec<_> = [1.0, 0.0, 0.0]
+                .iter()
+                .map(|&input| network.propagate_with_state(vec![input], &mut state)[0])
+                .collect();
+
+            assert_relative_eq!(outputs.as_slice(), [2.0, 1.0, 0.5].as_ref());
+        }
+
+        #[test]
+        fn reset() {
+            let network = network();
+            let mut state = network.state();
+
+            network.propagate_with_state(vec![1.0], &mut state);
+            state.reset();
+
+            let outputs = network
+                .propagate_with_state(vec![0.0], &mut state);
+
+            assert_relative_eq!(outputs.as_slice(), [0.0].as_ref());
+        }
+
+        #[test]
+        fn weights_round_trip() {
+            let network = network();
+
+            assert_eq!(network.inputs(), 1);
+            assert_eq!(network.weights(), vec![0.0, 1.0, 0.5, 0.0, 2.0]);
+            assert!(network.topology()[1].recurrent);
+        }
+    }
+
+    mod skips {
+        use super::*;
+
+        fn network() -> Network {
+            Network::builder(2)
+                .layer(1, Activation::Identity)
+                .layer(1, Activation::Identity)
+                .skip_from(0)
+                .build_from_weights(vec![
+                    0.1, 0.5, -0.5,
+                    // bias, hidden layer, then input layer
+                    0.2, 2.0, 0.3, -0.4
+                ])
+                .unwrap()
+        }
+
+        #[test]
+        fn propagate() {
+            let network = network();
+
+            let hidden = 0.1 + 0.5 * 1.0 - 0.5 * 0.5;
+            let expected = 0.2 + 2.0 * hidden + 0.3 * 1.0 - 0.4 * 0.5;
+
+            let output = network
+                .propagate(vec![1.0, 0.5]);
+
+            assert_relative_eq!(output[0], expected);
+        }
+
+        #[test]
+        fn propagate_with_state() {
+            let network = network();
+            let mut state = network.state();
+
+            let output = network
+                .propagate_with_state(vec![1.0, 0.5], &mut state);
+
+            assert_eq!(output, network.propagate(vec![1.0, 0.5]));
+        }
+
+        #[test]
+        fn topology() {
+            let network = network();
+
+            assert_eq!(network.topology()[2].skips, vec![0]);
+            assert_eq!(network.weights().len(), 7);
+        }
+    }
 }
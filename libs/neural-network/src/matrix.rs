@@ -0,0 +1,365 @@
+use super::*;
+
+// Same network as `Network`, but with every layer flattened into
+// a row-major weight matrix (one row per neuron) and a bias vector,
+// so that propagating doesn't allocate or chase pointers.
+// Like `Network::propagate`, it's stateless: recurrent layers
+// always see a zeroed state
+pub struct MatrixNetwork<F = f32> {
+    layers: Vec<MatrixLayer<F>>,
+}
+
+struct MatrixLayer<F> {
+    inputs: usize,
+    outputs: usize,
+    weights: Vec<F>,
+    biases: Vec<F>,
+    activation: Activation,
+    // see `LayerTopology::skips`
+    skips: Vec<usize>,
+}
+
+// Intermediate layer outputs; reuse it between calls
+// to keep propagation allocation-free
+#[derive(Default)]
+pub struct Scratch<F = f32> {
+    // outputs of every hidden layer, back-to-back
+    outputs: Vec<F>,
+    // inputs of layers with skip connections
+    gathered: Vec<F>,
+}
+
+impl<F: Float> Scratch<F> {
+    fn reserve(&mut self, outputs: usize, gathered: usize) {
+        if self.outputs.len() < outputs {
+            self.outputs.resize(outputs, F::zero());
+        }
+
+        if self.gathered.len() < gathered {
+            self.gathered.resize(gathered, F::zero());
+        }
+    }
+}
+
+impl<F: Float> MatrixLayer<F> {
+    fn propagate(&self, inputs: &[F], outputs: &mut [F]) {
+        let rows = inputs
+            .chunks_exact(self.inputs)
+            .zip(outputs.chunks_exact_mut(self.outputs));
+
+        for (input, output) in rows {
+            let neurons = output
+                .iter_mut()
+                .zip(self.weights.chunks_exact(self.inputs))
+                .zip(&self.biases);
+
+            for ((output, weights), bias) in neurons {
+                // same summation order as `Neuron::propagate`,
+                // so both networks give bit-identical results
+                let sum = input
+                    .iter()
+                    .zip(weights)
+                    .map(|(&input, &weight)| input * weight)
+                    .sum::<F>();
+
+                *output = self.activation.eval(*bias + sum);
+            }
+        }
+    }
+}
+
+impl<F: Float> MatrixNetwork<F> {
+    pub fn inputs(&self) -> usize {
+        self.layers[0].inputs
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.layers[self.layers.len() - 1].outputs
+    }
+
+    // scratch space big enough for a single input vector
+    pub fn scratch(&self) -> Scratch<F> {
+        let mut scratch = Scratch::default();
+        scratch.reserve(self.hidden(), self.widest_gathered());
+        scratch
+    }
+
+    pub fn propagate_into(&self, input: &[F], scratch: &mut Scratch<F>, out: &mut [F]) {
+        assert_eq!(input.len(), self.inputs());
+
+        self.propagate_batch(input, scratch, out);
+    }
+
+    // `inputs` holds N input vectors back-to-back,
+    // `out` receives the N matching output vectors
+    pub fn propagate_batch(&self, inputs: &[F], scratch: &mut Scratch<F>, out: &mut [F]) {
+        assert_eq!(inputs.len() % self.inputs(), 0);
+
+        let batch = inputs.len() / self.inputs();
+
+        assert_eq!(out.len(), batch * self.outputs());
+
+        scratch.reserve(batch * self.hidden(), batch * self.widest_gathered());
+
+        let Scratch { outputs, gathered } = scratch;
+        let last = self.layers.len() - 1;
+        let mut offset = 0;
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            // every layer only reads outputs of the layers before it
+            let (done, rest) = outputs.split_at_mut(offset);
+
+            // outputs of layer `source`, in `LayerTopology` indexing
+            let source = |source: usize| -> &[F] {
+                if source == 0 {
+                    inputs
+                } else {
+                    let start = batch * self.hidden_before(source - 1);
+                    &done[start..start + batch * self.layers[source - 1].outputs]
+                }
+            };
+
+            let src = if layer.skips.is_empty() {
+                source(idx)
+            } else {
+                let gathered = &mut gathered[..batch * layer.inputs];
+                let mut row_start = 0;
+
+                for row in 0..batch {
+                    for &from in std::iter::once(&idx).chain(&layer.skips) {
+                        let from = source(from);
+                        let len = from.len() / batch;
+
+                        gathered[row_start..row_start + len]
+                            .copy_from_slice(&from[row * len..(row + 1) * len]);
+                        row_start += len;
+                    }
+                }
+
+                &*gathered
+            };
+
+            let len = batch * layer.outputs;
+
+            let dst = if idx == last {
+                &mut *out
+            } else {
+                &mut rest[..len]
+            };
+
+            layer.propagate(src, dst);
+
+            offset += len;
+        }
+    }
+
+    // total size of all hidden layers' outputs
+    fn hidden(&self) -> usize {
+        self.hidden_before(self.layers.len() - 1)
+    }
+
+    // where the outputs of `layer` start in `Scratch::outputs`
+    fn hidden_before(&self, layer: usize) -> usize {
+        self.layers[..layer]
+            .iter()
+            .map(|layer| layer.outputs)
+            .sum()
+    }
+
+    fn widest_gathered(&self) -> usize {
+        self.layers
+            .iter()
+            .filter(|layer| !layer.skips.is_empty())
+            .map(|layer| layer.inputs)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<F: Float> From<&Network<F>> for MatrixNetwork<F> {
+    fn from(network: &Network<F>) -> Self {
+        let layers = network.layers
+            .iter()
+            .map(|layer| {
+                let inputs = layer.inputs();
+
+                MatrixLayer {
+                    inputs,
+                    outputs: layer.neurons.len(),
+                    // a zeroed state makes recurrent weights irrelevant
+                    weights: layer.neurons
+                        .iter()
+                        .flat_map(|neuron| neuron.weights[..inputs].iter().cloned())
+                        .collect(),
+                    biases: layer.neurons
+                        .iter()
+                        .map(|neuron| neuron.bias)
+                        .collect(),
+                    activation: layer.activation,
+                    skips: layer.skips.clone(),
+                }
+            })
+            .collect();
+
+        Self { layers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    fn network(rng: &mut dyn rand::RngCore) -> Network {
+        Network::builder(5)
+            .layer(8, Activation::ReLU)
+            .layer(3, Activation::Sigmoid)
+            .layer(2, Activation::Tanh)
+            .build_random(rng)
+            .unwrap()
+    }
+
+    mod from {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 2,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 2,
+                        ..Default::default()
+                    },
+                ],
+                vec![
+                    0.1, 0.2, 0.3,
+                    0.4, 0.5, 0.6
+                ]
+            );
+
+            let matrix = MatrixNetwork::from(&network);
+
+            assert_eq!(matrix.inputs(), 2);
+            assert_eq!(matrix.outputs(), 2);
+            assert_eq!(matrix.layers[0].weights, vec![0.2, 0.3, 0.5, 0.6]);
+            assert_eq!(matrix.layers[0].biases, vec![0.1, 0.4]);
+        }
+    }
+
+    mod propagate_into {
+        use super::*;
+
+        #[test]
+        fn matches_network() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = network(&mut rng);
+            let matrix = MatrixNetwork::from(&network);
+            let mut scratch = matrix.scratch();
+            let mut out = vec![0.0; matrix.outputs()];
+
+            for _ in 0..10 {
+                let input: Vec<f32> = (0..5)
+                    .map(|_| rng.gen_range(-1.0..=1.0))
+                    .collect();
+
+                matrix.propagate_into(&input, &mut scratch, &mut out);
+
+                assert_eq!(out, network.propagate(input));
+            }
+        }
+    }
+
+    mod propagate_batch {
+        use super::*;
+
+        #[test]
+        fn matches_network() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = network(&mut rng);
+            let matrix = MatrixNetwork::from(&network);
+            let mut scratch = Scratch::default();
+
+            let inputs: Vec<f32> = (0..7 * 5)
+                .map(|_| rng.gen_range(-1.0..=1.0))
+                .collect();
+            let mut out = vec![0.0; 7 * matrix.outputs()];
+
+            matrix.propagate_batch(&inputs, &mut scratch, &mut out);
+
+            let expected: Vec<f32> = inputs
+                .chunks(5)
+                .flat_map(|input| network.propagate(input.to_vec()))
+                .collect();
+
+            assert_eq!(out, expected);
+        }
+
+        #[test]
+        fn recurrent_matches_stateless_network() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(3)
+                .recurrent_layer(4, Activation::Tanh)
+                .layer(2, Activation::Identity)
+                .build_random(&mut rng)
+                .unwrap();
+            let matrix = MatrixNetwork::from(&network);
+
+            let inputs = vec![0.3, -0.7, 0.1];
+            let mut out = vec![0.0; 2];
+
+            matrix.propagate_batch(&inputs, &mut matrix.scratch(), &mut out);
+
+            let expected = network
+                .propagate(inputs);
+
+            assert_relative_eq!(out.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn skips_match_network() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(3)
+                .layer(4, Activation::ReLU)
+                .layer(2, Activation::Sigmoid)
+                .skip_from(0)
+                .layer(2, Activation::Tanh)
+                .skip_from(1)
+                .skip_from(0)
+                .build_random(&mut rng)
+                .unwrap();
+            let matrix = MatrixNetwork::from(&network);
+            let mut scratch = Scratch::default();
+
+            let inputs: Vec<f32> = (0..5 * 3)
+                .map(|_| rng.gen_range(-1.0..=1.0))
+                .collect();
+            let mut out = vec![0.0; 5 * 2];
+
+            matrix.propagate_batch(&inputs, &mut scratch, &mut out);
+
+            let expected: Vec<f32> = inputs
+                .chunks(3)
+                .flat_map(|input| network.propagate(input.to_vec()))
+                .collect();
+
+            assert_eq!(out, expected);
+        }
+
+        #[test]
+        fn empty_batch() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let matrix = MatrixNetwork::from(&network(&mut rng));
+            let mut out = vec![];
+
+            matrix.propagate_batch(&[], &mut Scratch::default(), &mut out);
+
+            assert!(out.is_empty());
+        }
+    }
+}
@@ -0,0 +1,666 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+
+use super::*;
+
+// Chance of a gene that's disabled in either parent
+// staying disabled in the child, as in the original NEAT paper
+const INHERIT_DISABLED: f64 = 0.75;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Input,
+    Hidden,
+    Output,
+}
+
+#[derive(Clone, Debug)]
+pub struct NodeGene {
+    pub id: usize,
+    pub kind: NodeKind,
+    // unused for input nodes
+    pub bias: f32,
+    pub activation: Activation,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConnectionGene {
+    pub innovation: usize,
+    pub from: usize,
+    pub to: usize,
+    pub weight: f32,
+    pub enabled: bool,
+}
+
+// Hands out node ids and innovation numbers, so that the same
+// structural mutation gets the same numbers across the whole population;
+// share one tracker between all genomes that are meant to be crossed
+#[derive(Debug, Default)]
+pub struct InnovationTracker {
+    next_node: usize,
+    next_innovation: usize,
+    connections: HashMap<(usize, usize), usize>,
+    // innovation of the split connection => id of the node put in its place
+    splits: HashMap<usize, usize>,
+}
+
+impl InnovationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn reserve_nodes(&mut self, nodes: usize) {
+        self.next_node = self.next_node.max(nodes);
+    }
+
+    fn node(&mut self) -> usize {
+        self.next_node += 1;
+        self.next_node - 1
+    }
+
+    fn connection(&mut self, from: usize, to: usize) -> usize {
+        let next_innovation = &mut self.next_innovation;
+
+        *self.connections
+            .entry((from, to))
+            .or_insert_with(|| {
+                *next_innovation += 1;
+                *next_innovation - 1
+            })
+    }
+
+    fn split(&mut self, innovation: usize) -> usize {
+        if let Some(&node) = self.splits.get(&innovation) {
+            return node;
+        }
+
+        let node = self.node();
+        self.splits.insert(innovation, node);
+        node
+    }
+}
+
+// Graph genome: nodes sorted by id, connections sorted by innovation.
+// Only feed-forward graphs are grown, so every genome compiles.
+#[derive(Clone, Debug)]
+pub struct Genome {
+    nodes: Vec<NodeGene>,
+    connections: Vec<ConnectionGene>,
+    hidden_activation: Activation,
+}
+
+impl Genome {
+    // every input connected to every output, no hidden nodes
+    pub fn minimal(
+        inputs: usize,
+        outputs: usize,
+        hidden_activation: Activation,
+        output_activation: Activation,
+        tracker: &mut InnovationTracker,
+        rng: &mut dyn RngCore
+    ) -> Self {
+        tracker.reserve_nodes(inputs + outputs);
+
+        let input_nodes = (0..inputs).map(|id| NodeGene {
+            id,
+            kind: NodeKind::Input,
+            bias: 0.0,
+            activation: Activation::Identity,
+        });
+
+        let output_nodes = (inputs..inputs + outputs).map(|id| NodeGene {
+            id,
+            kind: NodeKind::Output,
+            bias: rng.gen_range(-1.0..=1.0),
+            activation: output_activation,
+        });
+
+        let nodes = input_nodes
+            .chain(output_nodes)
+            .collect();
+
+        let mut connections = Vec::new();
+
+        for to in inputs..inputs + outputs {
+            for from in 0..inputs {
+                connections.push(ConnectionGene {
+                    innovation: tracker.connection(from, to),
+                    from,
+                    to,
+                    weight: rng.gen_range(-1.0..=1.0),
+                    enabled: true,
+                });
+            }
+        }
+
+        connections.sort_by_key(|connection| connection.innovation);
+
+        Self {
+            nodes,
+            connections,
+            hidden_activation,
+        }
+    }
+
+    pub fn nodes(&self) -> &[NodeGene] {
+        &self.nodes
+    }
+
+    pub fn connections(&self) -> &[ConnectionGene] {
+        &self.connections
+    }
+
+    // same semantics as `UniformMutation`: each weight and bias
+    // has `chance` to be nudged by up to +-`coeff`
+    pub fn mutate_weights(&mut self, rng: &mut dyn RngCore, chance: f32, coeff: f32) {
+        let biases = self.nodes
+            .iter_mut()
+            .filter(|node| node.kind != NodeKind::Input)
+            .map(|node| &mut node.bias);
+
+        let weights = self.connections
+            .iter_mut()
+            .map(|connection| &mut connection.weight);
+
+        for gene in biases.chain(weights) {
+            if rng.gen_bool(chance as _) {
+                *gene += rng.gen_range(-coeff..=coeff);
+            }
+        }
+    }
+
+    // Connects two so far unconnected nodes, keeping the graph acyclic;
+    // returns false if there's no such pair left
+    pub fn mutate_add_connection(
+        &mut self,
+        rng: &mut dyn RngCore,
+        tracker: &mut InnovationTracker
+    ) -> bool {
+        let mut candidates = Vec::new();
+
+        for from in &self.nodes {
+            if from.kind == NodeKind::Output {
+                continue;
+            }
+
+            for to in &self.nodes {
+                if to.kind == NodeKind::Input || from.id == to.id {
+                    continue;
+                }
+
+                let connected = self.connections
+                    .iter()
+                    .any(|connection| connection.from == from.id && connection.to == to.id);
+
+                if !connected && !self.reaches(to.id, from.id) {
+                    candidates.push((from.id, to.id));
+                }
+            }
+        }
+
+        let (from, to) = match candidates.choose(rng) {
+            Some(&candidate) => candidate,
+            None => return false,
+        };
+
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection(from, to),
+            from,
+            to,
+            weight: rng.gen_range(-1.0..=1.0),
+            enabled: true,
+        });
+
+        true
+    }
+
+    // Splits an enabled connection in two, with a new hidden node
+    // in between; returns false if there's nothing to split
+    pub fn mutate_add_node(
+        &mut self,
+        rng: &mut dyn RngCore,
+        tracker: &mut InnovationTracker
+    ) -> bool {
+        let enabled: Vec<_> = (0..self.connections.len())
+            .filter(|&idx| self.connections[idx].enabled)
+            .collect();
+
+        let idx = match enabled.choose(rng) {
+            Some(&idx) => idx,
+            None => return false,
+        };
+
+        let split = self.connections[idx].clone();
+        self.connections[idx].enabled = false;
+
+        let mut node = tracker.split(split.innovation);
+
+        // the same connection got split before, e.g. after being re-enabled
+        if self.node_index(node).is_some() {
+            node = tracker.node();
+        }
+
+        let idx = self.nodes.partition_point(|gene| gene.id < node);
+
+        self.nodes.insert(idx, NodeGene {
+            id: node,
+            kind: NodeKind::Hidden,
+            bias: 0.0,
+            activation: self.hidden_activation,
+        });
+
+        // keep the signal (almost) unchanged right after the split
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection(split.from, node),
+            from: split.from,
+            to: node,
+            weight: 1.0,
+            enabled: true,
+        });
+
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection(node, split.to),
+            from: node,
+            to: split.to,
+            weight: split.weight,
+            enabled: true,
+        });
+
+        true
+    }
+
+    // Flips the enable flag of a random connection
+    pub fn mutate_toggle_connection(&mut self, rng: &mut dyn RngCore) -> bool {
+        match self.connections.choose_mut(rng) {
+            Some(connection) => {
+                connection.enabled = !connection.enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Genes are aligned by innovation number: matching genes come from
+    // either parent at random, disjoint and excess ones from `fitter`
+    pub fn crossover(fitter: &Genome, other: &Genome, rng: &mut dyn RngCore) -> Self {
+        let other_connections: HashMap<_, _> = other.connections
+            .iter()
+            .map(|connection| (connection.innovation, connection))
+            .collect();
+
+        let connections = fitter.connections
+            .iter()
+            .map(|gene| match other_connections.get(&gene.innovation) {
+                Some(other_gene) => {
+                    let mut child = if rng.gen_bool(0.5) {
+                        gene.clone()
+                    } else {
+                        (*other_gene).clone()
+                    };
+
+                    child.enabled = if !gene.enabled || !other_gene.enabled {
+                        !rng.gen_bool(INHERIT_DISABLED)
+                    } else {
+                        true
+                    };
+
+                    child
+                }
+                None => gene.clone(),
+            })
+            .collect();
+
+        let nodes = fitter.nodes
+            .iter()
+            .map(|gene| match other.node_index(gene.id) {
+                Some(idx) if rng.gen_bool(0.5) => other.nodes[idx].clone(),
+                _ => gene.clone(),
+            })
+            .collect();
+
+        Self {
+            nodes,
+            connections,
+            hidden_activation: fitter.hidden_activation,
+        }
+    }
+
+    pub fn compile(&self) -> NeatNetwork {
+        let mut incoming = vec![Vec::new(); self.nodes.len()];
+        let mut outgoing = vec![Vec::new(); self.nodes.len()];
+        let mut pending = vec![0; self.nodes.len()];
+
+        for connection in self.connections.iter().filter(|connection| connection.enabled) {
+            let from = self.node_index(connection.from).unwrap();
+            let to = self.node_index(connection.to).unwrap();
+
+            incoming[to].push((from, connection.weight));
+            outgoing[from].push(to);
+            pending[to] += 1;
+        }
+
+        // Kahn's algorithm, so that every node is evaluated
+        // after all of its inputs
+        let mut ready: Vec<_> = (0..self.nodes.len())
+            .filter(|&idx| pending[idx] == 0)
+            .rev()
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while let Some(idx) = ready.pop() {
+            order.push(idx);
+
+            for &to in &outgoing[idx] {
+                pending[to] -= 1;
+
+                if pending[to] == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+
+        let nodes = order
+            .into_iter()
+            .filter(|&idx| self.nodes[idx].kind != NodeKind::Input)
+            .map(|idx| NeatNode {
+                idx,
+                bias: self.nodes[idx].bias,
+                activation: self.nodes[idx].activation,
+                incoming: incoming[idx].clone(),
+            })
+            .collect();
+
+        NeatNetwork {
+            size: self.nodes.len(),
+            inputs: self.indices(NodeKind::Input),
+            outputs: self.indices(NodeKind::Output),
+            nodes,
+        }
+    }
+
+    fn node_index(&self, id: usize) -> Option<usize> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+    }
+
+    fn indices(&self, kind: NodeKind) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|&idx| self.nodes[idx].kind == kind)
+            .collect()
+    }
+
+    fn insert_connection(&mut self, connection: ConnectionGene) {
+        let idx = self.connections
+            .partition_point(|gene| gene.innovation < connection.innovation);
+
+        self.connections.insert(idx, connection);
+    }
+
+    // whether there's a path (of any connections) from `from` to `to`
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut stack = vec![from];
+        let mut visited = vec![from];
+
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+
+            for connection in &self.connections {
+                if connection.from == node && !visited.contains(&connection.to) {
+                    visited.push(connection.to);
+                    stack.push(connection.to);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+// Executable form of a `Genome`
+#[derive(Debug)]
+pub struct NeatNetwork {
+    size: usize,
+    inputs: Vec<usize>,
+    outputs: Vec<usize>,
+    // topologically sorted, input nodes excluded
+    nodes: Vec<NeatNode>,
+}
+
+#[derive(Debug)]
+struct NeatNode {
+    idx: usize,
+    bias: f32,
+    activation: Activation,
+    incoming: Vec<(usize, f32)>,
+}
+
+impl NeatNetwork {
+    pub fn inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        assert_eq!(inputs.len(), self.inputs.len());
+
+        let mut values = vec![0.0; self.size];
+
+        for (&idx, input) in self.inputs.iter().zip(inputs) {
+            values[idx] = input;
+        }
+
+        for node in &self.nodes {
+            let output = node.incoming
+                .iter()
+                .map(|&(from, weight)| values[from] * weight)
+                .sum::<f32>();
+
+            values[node.idx] = node.activation.apply(node.bias + output);
+        }
+
+        self.outputs
+            .iter()
+            .map(|&idx| values[idx])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    fn genome(tracker: &mut InnovationTracker, rng: &mut dyn RngCore) -> Genome {
+        Genome::minimal(2, 1, Activation::ReLU, Activation::Identity, tracker, rng)
+    }
+
+    mod minimal {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut tracker = InnovationTracker::new();
+            let genome = genome(&mut tracker, &mut rng);
+
+            assert_eq!(genome.nodes().len(), 3);
+            assert_eq!(genome.connections().len(), 2);
+
+            let innovations: Vec<_> = genome.connections()
+                .iter()
+                .map(|connection| connection.innovation)
+                .collect();
+
+            assert_eq!(innovations, vec![0, 1]);
+        }
+
+        #[test]
+        fn shares_innovations() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut tracker = InnovationTracker::new();
+            let mut a = genome(&mut tracker, &mut rng);
+            let mut b = genome(&mut tracker, &mut rng);
+
+            // same split in both genomes => same node and innovations
+            a.connections[1].enabled = false;
+            b.connections[1].enabled = false;
+
+            assert!(a.mutate_add_node(&mut rng, &mut tracker));
+            assert!(b.mutate_add_node(&mut rng, &mut tracker));
+
+            let ids = |genome: &Genome| -> Vec<_> {
+                genome.connections()
+                    .iter()
+                    .map(|connection| (connection.innovation, connection.from, connection.to))
+                    .collect()
+            };
+
+            assert_eq!(ids(&a), ids(&b));
+        }
+    }
+
+    mod mutate_add_node {
+        use super::*;
+
+        #[test]
+        fn keeps_signal() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut tracker = InnovationTracker::new();
+            let mut genome = genome(&mut tracker, &mut rng);
+
+            // positive inputs and a ReLU hidden node with zero bias
+            // give the very same output after the split
+            let before = genome
+                .compile()
+                .propagate(vec![0.3, 0.7]);
+
+            assert!(genome.mutate_add_node(&mut rng, &mut tracker));
+
+            let after = genome
+                .compile()
+                .propagate(vec![0.3, 0.7]);
+
+            assert_eq!(genome.nodes().len(), 4);
+            assert_eq!(genome.connections().len(), 4);
+            assert_eq!(
+                genome.connections()
+                    .iter()
+                    .filter(|connection| !connection.enabled)
+                    .count(),
+                1
+            );
+            assert_relative_eq!(before.as_slice(), after.as_slice());
+        }
+    }
+
+    mod mutate_add_connection {
+        use super::*;
+
+        #[test]
+        fn stays_acyclic() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut tracker = InnovationTracker::new();
+            let mut genome = genome(&mut tracker, &mut rng);
+
+            for _ in 0..5 {
+                genome.mutate_add_node(&mut rng, &mut tracker);
+            }
+
+            while genome.mutate_add_connection(&mut rng, &mut tracker) {}
+
+            for connection in genome.connections() {
+                assert!(!genome.reaches(connection.to, connection.from));
+            }
+
+            // every non-input node got evaluated
+            assert_eq!(genome.compile().nodes.len(), genome.nodes().len() - 2);
+        }
+
+        #[test]
+        fn minimal_genome_is_saturated() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut tracker = InnovationTracker::new();
+            let mut genome = genome(&mut tracker, &mut rng);
+
+            assert!(!genome.mutate_add_connection(&mut rng, &mut tracker));
+        }
+    }
+
+    mod crossover {
+        use super::*;
+
+        #[test]
+        fn takes_structure_from_fitter() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut tracker = InnovationTracker::new();
+            let mut fitter = genome(&mut tracker, &mut rng);
+            let other = genome(&mut tracker, &mut rng);
+
+            fitter.mutate_add_node(&mut rng, &mut tracker);
+
+            let child = Genome::crossover(&fitter, &other, &mut rng);
+
+            assert_eq!(child.nodes().len(), fitter.nodes().len());
+            assert_eq!(child.connections().len(), fitter.connections().len());
+
+            for (child, fitter) in child.connections().iter().zip(fitter.connections()) {
+                assert_eq!(child.innovation, fitter.innovation);
+
+                // genes only the fitter parent has are copied verbatim
+                if other.connections().iter().all(|gene| gene.innovation != fitter.innovation) {
+                    assert_relative_eq!(child.weight, fitter.weight);
+                }
+            }
+        }
+    }
+
+    mod compile {
+        use super::*;
+
+        #[test]
+        fn propagate() {
+            let mut tracker = InnovationTracker::new();
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut genome = genome(&mut tracker, &mut rng);
+
+            genome.nodes[2].bias = 0.1;
+            genome.connections[0].weight = 0.5;
+            genome.connections[1].weight = -0.25;
+
+            let network = genome.compile();
+
+            assert_eq!(network.inputs(), 2);
+            assert_eq!(network.outputs(), 1);
+            assert_relative_eq!(
+                network.propagate(vec![0.4, 0.8])[0],
+                (0.5 * 0.4) + (-0.25 * 0.8) + 0.1
+            );
+        }
+
+        #[test]
+        fn skips_disabled_connections() {
+            let mut tracker = InnovationTracker::new();
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut genome = genome(&mut tracker, &mut rng);
+
+            genome.nodes[2].bias = 0.0;
+            genome.connections[0].weight = 0.5;
+            genome.connections[1].enabled = false;
+
+            assert_relative_eq!(
+                genome.compile().propagate(vec![1.0, 1.0])[0],
+                0.5
+            );
+        }
+    }
+}
@@ -1,28 +1,35 @@
-use rand::Rng;
+use super::activation::Activation;
+use super::float::Float;
+use super::initializer::Sampler;
 
-pub struct Neuron {
-    pub(crate) bias: f32, // Neuron's bias
-    pub(crate) weights: Vec<f32>, // incoming synapse weights
+pub struct Neuron<F = f32> {
+    pub(crate) bias: F, // Neuron's bias
+    pub(crate) weights: Vec<F>, // incoming synapse weights
 }
 
-impl Neuron {
-    pub(crate) fn randomize(output_size: usize, rng: &mut dyn rand::RngCore) -> Self {
-        let bias = rng.gen_range(-1.0..=1.0);
+impl<F: Float> Neuron<F> {
+    pub(crate) fn randomize(
+        output_size: usize,
+        weights: &Sampler<F>,
+        bias: &Sampler<F>,
+        rng: &mut dyn rand::RngCore
+    ) -> Self {
+        let bias = bias.sample(rng);
 
         let weights = (0..output_size)
-            .map(|_| rng.gen_range(-1.0..=1.0))
+            .map(|_| weights.sample(rng))
             .collect();
 
         Self { bias, weights }
     }
 
-    pub fn from_weights(
+    pub(crate) fn from_weights(
         output_neurons: usize,
-        weights: &mut dyn Iterator<Item = f32>
+        weights: &mut dyn Iterator<Item = F>
     ) -> Self {
         let bias = weights
             .next()
-            .expect("not enought weights!");
+            .expect("not enough weights!");
 
         let weights = (0..output_neurons)
             .map(|_| weights.next().expect("not enough weights!"))
@@ -34,17 +41,22 @@ impl Neuron {
         }
     }
 
-    pub(crate) fn propagate(&self, inputs: &Vec<f32>) -> f32 {
+    pub(crate) fn propagate(&self, inputs: &[F], activation: Activation) -> F {
+        activation.eval(self.sum(inputs))
+    }
+
+    // pre-activation output
+    pub(crate) fn sum(&self, inputs: &[F]) -> F {
         // number of inputs to neuron == number of synapses(weights)
         assert_eq!(inputs.len(), self.weights.len());
 
         let output = inputs
             .iter()
             .zip(&self.weights)
-            .map(|(input, weight)| input * weight)
-            .sum::<f32>();
+            .map(|(input, &weight)| *input * weight)
+            .sum::<F>();
 
-        (self.bias + output).max(0.0)
+        self.bias + output
     }
 }
 
@@ -54,6 +66,7 @@ mod tests {
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
     use approx::assert_relative_eq;
+    use crate::initializer::Initializer;
 
     mod neuron {
         use super::*;
@@ -61,7 +74,8 @@ mod tests {
         #[test]
         fn randomize_test() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let neuron = Neuron::randomize(4, &mut rng);
+            let (weights, bias) = Initializer::default().samplers::<f32>(4, 1);
+            let neuron: Neuron = Neuron::randomize(4, &weights, &bias, &mut rng);
 
             assert_relative_eq!(neuron.bias, -0.6255188);
 
@@ -73,20 +87,38 @@ mod tests {
 
         #[test]
         fn propagate_test() {
-            let neuron = Neuron {
+            let neuron: Neuron = Neuron {
                 bias: 0.5,
                 weights: vec![0.5, 0.1, 0.35],
             };
 
             assert_relative_eq!(
-                neuron.propagate(&vec![-10.0, -15.0, -5.0]),
+                neuron.propagate(&[-10.0, -15.0, -5.0], Activation::ReLU),
                 0.0
             );
 
             assert_relative_eq!(
-                neuron.propagate(&vec![0.79, 0.97, 0.13]),
+                neuron.propagate(&[0.79, 0.97, 0.13], Activation::ReLU),
                 (0.5 * 0.79) + (0.1 * 0.97) + (0.35 * 0.13) + 0.5,
             );
         }
+
+        #[test]
+        fn propagate_with_activation_test() {
+            let neuron: Neuron = Neuron {
+                bias: 0.5,
+                weights: vec![0.5, 0.1, 0.35],
+            };
+
+            assert_relative_eq!(
+                neuron.propagate(&[-10.0, -15.0, -5.0], Activation::Identity),
+                (0.5 * -10.0) + (0.1 * -15.0) + (0.35 * -5.0) + 0.5,
+            );
+
+            assert_relative_eq!(
+                neuron.propagate(&[-10.0, -15.0, -5.0], Activation::Tanh),
+                (-7.75_f32).tanh(),
+            );
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,388 @@
+use rand::{Rng, RngCore};
+
+use super::*;
+
+// How a connection's weight changes after every propagation, given the
+// activity of the neuron it comes from (`pre`) and goes into (`post`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HebbianRule {
+    // eta * pre * post
+    Hebb,
+    // eta * post * (pre - post * weight), which keeps weights bounded
+    Oja,
+    // eta * (a * pre * post + b * pre + c * post + d)
+    Abcd,
+}
+
+impl HebbianRule {
+    // coefficients per connection: eta, then a, b, c, d for `Abcd`
+    pub fn coefficients(&self) -> usize {
+        match self {
+            Self::Hebb | Self::Oja => 1,
+            Self::Abcd => 5,
+        }
+    }
+
+    // computed in `F`, so that `f64` networks keep their precision
+    fn delta<F: Float>(&self, coefficients: &[f32], pre: F, post: F, weight: F) -> F {
+        let eta = F::from_f32(coefficients[0]);
+
+        match self {
+            Self::Hebb => eta * pre * post,
+            Self::Oja => eta * post * (pre - post * weight),
+            Self::Abcd => {
+                let [a, b, c, d] = [1, 2, 3, 4].map(|idx| F::from_f32(coefficients[idx]));
+
+                eta * (a * pre * post + b * pre + c * post + d)
+            }
+        }
+    }
+}
+
+// Per-connection learning rule coefficients of a network, biases excluded.
+//
+// Coefficients are laid out in the same order as the weights in
+// `Network::weights`, `rule.coefficients()` values per connection
+#[derive(Clone, Debug)]
+pub struct Plasticity {
+    rule: HebbianRule,
+    coefficients: Vec<f32>,
+    // weights are kept within -limit..=limit
+    limit: Option<f32>,
+}
+
+impl Plasticity {
+    pub fn randomize<F: Float>(
+        rule: HebbianRule,
+        network: &Network<F>,
+        rng: &mut dyn RngCore
+    ) -> Self {
+        let coefficients = (0..Self::coefficients_count(rule, network))
+            .map(|_| rng.gen_range(-1.0..=1.0))
+            .collect();
+
+        Self::new(rule, coefficients)
+    }
+
+    pub fn from_coefficients<F: Float>(
+        rule: HebbianRule,
+        network: &Network<F>,
+        coefficients: impl IntoIterator<Item = f32>
+    ) -> Result<Self, NetworkError> {
+        let coefficients: Vec<f32> = coefficients.into_iter().collect();
+        let plasticity = Self::new(rule, coefficients);
+
+        plasticity.check(network)?;
+
+        Ok(plasticity)
+    }
+
+    pub fn coefficients_count<F: Float>(rule: HebbianRule, network: &Network<F>) -> usize {
+        let connections: usize = network.layers
+            .iter()
+            .flat_map(|layer| &layer.neurons)
+            .map(|neuron| neuron.weights.len())
+            .sum();
+
+        rule.coefficients() * connections
+    }
+
+    pub fn limit(mut self, limit: f32) -> Self {
+        assert!(limit > 0.0);
+
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn rule(&self) -> HebbianRule {
+        self.rule
+    }
+
+    pub fn coefficients(&self) -> &[f32] {
+        &self.coefficients
+    }
+
+    fn check<F: Float>(&self, network: &Network<F>) -> Result<(), NetworkError> {
+        let expected = Self::coefficients_count(self.rule, network);
+
+        if self.coefficients.len() != expected {
+            return Err(NetworkError::CoefficientCount {
+                expected,
+                actual: self.coefficients.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn new(rule: HebbianRule, coefficients: Vec<f32>) -> Self {
+        Self {
+            rule,
+            coefficients,
+            limit: None,
+        }
+    }
+}
+
+impl<F: Float> Network<F> {
+    // `propagate_with_state`, followed by a single step of `plasticity`'s
+    // rule over every connection; fails when `plasticity` was made for
+    // a network with a different number of connections
+    pub fn propagate_plastic(
+        &mut self,
+        inputs: Vec<F>,
+        state: &mut NetworkState<F>,
+        plasticity: &Plasticity
+    ) -> Result<Vec<F>, NetworkError> {
+        plasticity.check(self)?;
+
+        // recurrent weights learn from what the layer saw
+        let previous = state.layers.clone();
+        let trace = self.propagate_traced_with_state(inputs, state);
+
+        let mut outputs = vec![trace.inputs.clone()];
+        outputs.extend(trace.layers.iter().map(|layer| layer.outputs.clone()));
+
+        let mut coefficients = plasticity.coefficients.chunks(plasticity.rule.coefficients());
+
+        for (idx, layer) in self.layers.iter_mut().enumerate() {
+            let mut pre = layer.gather(&outputs[..=idx]);
+            pre.extend_from_slice(&previous[idx]);
+
+            for (neuron, &post) in layer.neurons.iter_mut().zip(&outputs[idx + 1]) {
+                for (weight, &pre) in neuron.weights.iter_mut().zip(&pre) {
+                    let delta = plasticity.rule.delta(
+                        coefficients.next().unwrap(),
+                        pre,
+                        post,
+                        *weight
+                    );
+
+                    let mut updated = *weight + delta;
+
+                    if let Some(limit) = plasticity.limit {
+                        let limit = F::from_f32(limit);
+
+                        updated = updated.max(-limit).min(limit);
+                    }
+
+                    *weight = updated;
+                }
+            }
+        }
+
+        Ok(outputs.pop().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    // 2 inputs => 1 identity neuron
+    fn network() -> Network {
+        Network::from_weights(
+            &[
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 1,
+                    ..Default::default()
+                },
+            ],
+            vec![0.0, 0.5, -0.5]
+        )
+    }
+
+    mod coefficients_count {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(3)
+                .recurrent_layer(2, Activation::Tanh)
+                .layer(1, Activation::Identity)
+                .build_random(&mut rng)
+                .unwrap();
+
+            // (3 + 2) * 2 + 2 * 1 connections
+            assert_eq!(Plasticity::coefficients_count(HebbianRule::Hebb, &network), 12);
+            assert_eq!(Plasticity::coefficients_count(HebbianRule::Abcd, &network), 60);
+            assert_eq!(
+                Plasticity::randomize(HebbianRule::Oja, &network, &mut rng).coefficients().len(),
+                12
+            );
+        }
+    }
+
+    mod from_coefficients {
+        use super::*;
+
+        #[test]
+        fn wrong_count() {
+            let network = network();
+
+            assert_eq!(
+                Plasticity::from_coefficients(HebbianRule::Abcd, &network, [0.0; 9]).unwrap_err(),
+                NetworkError::CoefficientCount { expected: 10, actual: 9 }
+            );
+        }
+    }
+
+    mod propagate_plastic {
+        use super::*;
+
+        #[test]
+        fn hebb() {
+            let mut network = network();
+            let plasticity = Plasticity::from_coefficients(HebbianRule::Hebb, &network, [0.1, 1.0]).unwrap();
+            let mut state = network.state();
+
+            // output = 0.5 * 1.0 - 0.5 * 0.5 = 0.25
+            let outputs = network.propagate_plastic(vec![1.0, 0.5], &mut state, &plasticity).unwrap();
+
+            assert_relative_eq!(outputs[0], 0.25);
+            let weights = network.weights();
+
+            assert_relative_eq!(weights.as_slice(), [0.0, 0.525, -0.375].as_ref());
+        }
+
+        #[test]
+        fn oja() {
+            let mut network = network();
+            let plasticity = Plasticity::from_coefficients(HebbianRule::Oja, &network, [0.1, 0.1]).unwrap();
+            let mut state = network.state();
+
+            network.propagate_plastic(vec![1.0, 0.5], &mut state, &plasticity).unwrap();
+
+            // 0.1 * 0.25 * (pre - 0.25 * weight)
+            let weights = network.weights();
+
+            assert_relative_eq!(
+                weights.as_slice(),
+                [0.0, 0.5 + 0.025 * (1.0 - 0.125), -0.5 + 0.025 * (0.5 + 0.125)].as_ref()
+            );
+        }
+
+        #[test]
+        fn abcd() {
+            let mut network = network();
+            let plasticity = Plasticity::from_coefficients(HebbianRule::Abcd, &network, [
+                1.0, 0.0, 0.0, 0.0, 0.5,
+                1.0, 1.0, 1.0, 1.0, 1.0,
+            ]).unwrap();
+            let mut state = network.state();
+
+            network.propagate_plastic(vec![1.0, 0.5], &mut state, &plasticity).unwrap();
+
+            let weights = network.weights();
+
+            assert_relative_eq!(
+                weights.as_slice(),
+                [0.0, 1.0, -0.5 + 0.125 + 0.5 + 0.25 + 1.0].as_ref()
+            );
+        }
+
+        #[test]
+        fn limit() {
+            let mut network = network();
+            let plasticity = Plasticity::from_coefficients(HebbianRule::Abcd, &network, [
+                1.0, 0.0, 0.0, 0.0, 10.0,
+                1.0, 0.0, 0.0, 0.0, -10.0,
+            ]).unwrap()
+                .limit(2.0);
+            let mut state = network.state();
+
+            network.propagate_plastic(vec![1.0, 0.5], &mut state, &plasticity).unwrap();
+
+            assert_eq!(network.weights(), vec![0.0, 2.0, -2.0]);
+        }
+
+        #[test]
+        fn recurrent() {
+            // a single recurrent identity neuron, learning from its own
+            // previous output
+            let mut network = Network::builder(1)
+                .recurrent_layer(1, Activation::Identity)
+                .build_from_weights(vec![0.0, 1.0, 0.0])
+                .unwrap();
+            let plasticity = Plasticity::from_coefficients(HebbianRule::Hebb, &network, [0.0, 1.0]).unwrap();
+            let mut state = network.state();
+
+            network.propagate_plastic(vec![1.0], &mut state, &plasticity).unwrap();
+            // previous output was 0, nothing to learn yet
+            assert_eq!(network.weights(), vec![0.0, 1.0, 0.0]);
+
+            network.propagate_plastic(vec![1.0], &mut state, &plasticity).unwrap();
+            // pre = 1 (previous output), post = 1
+            assert_eq!(network.weights(), vec![0.0, 1.0, 1.0]);
+        }
+
+        #[test]
+        fn other_network() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let plasticity = Plasticity::from_coefficients(HebbianRule::Hebb, &network(), [0.0, 0.0]).unwrap();
+            let mut other = Network::builder(3)
+                .layer(1, Activation::Identity)
+                .build_random(&mut rng)
+                .unwrap();
+            let mut state = other.state();
+
+            assert_eq!(
+                other.propagate_plastic(vec![0.0; 3], &mut state, &plasticity).unwrap_err(),
+                NetworkError::CoefficientCount { expected: 3, actual: 2 }
+            );
+        }
+
+        #[test]
+        fn keeps_f64_precision() {
+            let mut network = NetworkBuilder::new(2)
+                .layer(1, Activation::Identity)
+                .scalar::<f64>()
+                .build_from_weights(vec![0.1, 0.2, 0.3])
+                .unwrap();
+            let plasticity = Plasticity::from_coefficients(HebbianRule::Hebb, &network, [0.0, 0.0]).unwrap();
+            let mut state = network.state();
+
+            network.propagate_plastic(vec![1.0, 1.0], &mut state, &plasticity).unwrap();
+
+            assert_eq!(network.weights(), vec![0.1, 0.2, 0.3]);
+        }
+
+        #[test]
+        fn zero_coefficients_match_propagate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(3)
+                .recurrent_layer(4, Activation::Tanh)
+                .layer(2, Activation::Sigmoid)
+                .skip_from(0)
+                .build_random(&mut rng)
+                .unwrap();
+
+            let mut plastic = Network::from_weights(&network.topology(), network.weights());
+            let plasticity = Plasticity::from_coefficients(
+                HebbianRule::Abcd,
+                &plastic,
+                vec![0.0; Plasticity::coefficients_count(HebbianRule::Abcd, &plastic)]
+            ).unwrap();
+
+            let mut state = network.state();
+            let mut plastic_state = plastic.state();
+
+            for _ in 0..3 {
+                assert_eq!(
+                    plastic.propagate_plastic(vec![0.1, 0.2, 0.3], &mut plastic_state, &plasticity).unwrap(),
+                    network.propagate_with_state(vec![0.1, 0.2, 0.3], &mut state)
+                );
+            }
+
+            assert_eq!(plastic.weights(), network.weights());
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use super::*;
+
+// Number of bins in `NetworkStats::histograms`
+pub const HISTOGRAM_BINS: usize = 10;
+
+#[derive(Clone, Debug)]
+pub struct NetworkStats {
+    // non-zero weights, biases excluded
+    pub connections: usize,
+    // all weights, biases excluded
+    pub synapses: usize,
+    // fraction of zeroed weights, in 0..=1
+    pub sparsity: f32,
+    // Per non-input layer: neurons that output 0 for every sample,
+    // e.g. ReLUs stuck below zero
+    pub dead: Vec<usize>,
+    // per non-input layer, over its weights (biases included)
+    pub histograms: Vec<Histogram>,
+}
+
+// Equal-width bins spanning `min..=max`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    pub min: f32,
+    pub max: f32,
+    pub bins: Vec<usize>,
+}
+
+impl Histogram {
+    pub fn new(values: &[f32], bins: usize) -> Self {
+        assert!(bins > 0);
+
+        let min = values.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+        let mut counts = vec![0; bins];
+
+        for &value in values {
+            let bin = if max > min {
+                ((value - min) / (max - min) * bins as f32) as usize
+            } else {
+                0
+            };
+
+            // `max` itself lands in the last bin
+            counts[bin.min(bins - 1)] += 1;
+        }
+
+        Self { min, max, bins: counts }
+    }
+}
+
+impl<F: Float> Network<F> {
+    // Zeroes every weight (biases excluded) whose magnitude is below
+    // `threshold`, then removes hidden neurons that don't matter anymore:
+    // the ones nothing reads from, and the ones without any incoming
+    // weights, whose constant output gets folded into the biases of
+    // their readers.
+    //
+    // Outputs don't change, save for rounding errors. Neurons can go away,
+    // so states created before pruning must not be used afterwards.
+    pub fn prune(&mut self, threshold: F) {
+        for neuron in self.layers.iter_mut().flat_map(|layer| &mut layer.neurons) {
+            for weight in &mut neuron.weights {
+                if weight.abs() < threshold {
+                    *weight = F::zero();
+                }
+            }
+        }
+
+        // removing a neuron can leave its sources unread, hence the loop
+        while self.remove_dead_neuron() {}
+    }
+
+    pub fn stats(&self, samples: &[Vec<F>]) -> NetworkStats {
+        let weights = self.layers
+            .iter()
+            .flat_map(|layer| &layer.neurons)
+            .flat_map(|neuron| &neuron.weights);
+
+        let synapses = weights.clone().count();
+        let connections = weights
+            .filter(|weight| **weight != F::zero())
+            .count();
+
+        let sparsity = if synapses == 0 {
+            0.0
+        } else {
+            1.0 - connections as f32 / synapses as f32
+        };
+
+        let mut alive: Vec<Vec<bool>> = self.layers
+            .iter()
+            .map(|layer| vec![false; layer.neurons.len()])
+            .collect();
+
+        for sample in samples {
+            let trace = self.propagate_traced(sample.clone());
+
+            for (alive, layer) in alive.iter_mut().zip(&trace.layers) {
+                for (alive, output) in alive.iter_mut().zip(&layer.outputs) {
+                    *alive |= *output != F::zero();
+                }
+            }
+        }
+
+        let dead = alive
+            .iter()
+            .map(|alive| alive.iter().filter(|alive| !**alive).count())
+            .collect();
+
+        let histograms = self.layers
+            .iter()
+            .map(|layer| {
+                let weights: Vec<f32> = layer.neurons
+                    .iter()
+                    .flat_map(|neuron| std::iter::once(&neuron.bias).chain(&neuron.weights))
+                    .map(|weight| Float::to_f32(*weight))
+                    .collect();
+
+                Histogram::new(&weights, HISTOGRAM_BINS)
+            })
+            .collect();
+
+        NetworkStats { connections, synapses, sparsity, dead, histograms }
+    }
+
+    // Removes a single removable hidden neuron, if there's one
+    fn remove_dead_neuron(&mut self) -> bool {
+        // the output layer is left intact
+        for layer in 0..self.layers.len() - 1 {
+            // an empty layer would lose its input size
+            if self.layers[layer].neurons.len() == 1 {
+                continue;
+            }
+
+            let readers = self.readers(layer);
+
+            for neuron in 0..self.layers[layer].neurons.len() {
+                let reads = |reader: usize, offset: usize, own: bool| {
+                    self.layers[reader].neurons
+                        .iter()
+                        .enumerate()
+                        .filter(|(idx, _)| !own || *idx != neuron)
+                        .any(|(_, other)| other.weights[offset + neuron] != F::zero())
+                };
+
+                // the neuron's state can't be folded into a bias, as it
+                // starts out at zero
+                let recurrent = readers
+                    .iter()
+                    .any(|&(reader, offset)| reader == layer && reads(reader, offset, true));
+
+                let read = readers
+                    .iter()
+                    .any(|&(reader, offset)| reader != layer && reads(reader, offset, false));
+
+                let constant = self.layers[layer].neurons[neuron].weights
+                    .iter()
+                    .all(|weight| *weight == F::zero());
+
+                if !recurrent && (!read || constant) {
+                    self.remove_neuron(layer, neuron, &readers);
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn remove_neuron(&mut self, layer: usize, neuron: usize, readers: &[(usize, usize)]) {
+        let removed = &self.layers[layer].neurons[neuron];
+        let output = self.layers[layer].activation.eval(removed.bias);
+
+        for &(reader, offset) in readers {
+            for other in &mut self.layers[reader].neurons {
+                let weight = other.weights.remove(offset + neuron);
+
+                if reader != layer {
+                    other.bias = other.bias + weight * output;
+                }
+            }
+        }
+
+        self.layers[layer].neurons.remove(neuron);
+    }
+
+    // Every (layer, offset) whose neurons read the outputs of `layer`,
+    // starting at `offset` within their weights
+    fn readers(&self, layer: usize) -> Vec<(usize, usize)> {
+        // `skips` index the outputs, where 0 stands for the inputs
+        let size = |output: usize| {
+            if output == 0 {
+                self.inputs()
+            } else {
+                self.layers[output - 1].neurons.len()
+            }
+        };
+
+        let mut readers = Vec::new();
+
+        if self.layers[layer].recurrent {
+            readers.push((layer, self.layers[layer].inputs()));
+        }
+
+        if layer + 1 < self.layers.len() {
+            readers.push((layer + 1, 0));
+        }
+
+        for (reader, other) in self.layers.iter().enumerate().skip(layer + 2) {
+            let mut offset = size(reader);
+
+            for &skip in &other.skips {
+                if skip == layer + 1 {
+                    readers.push((reader, offset));
+                }
+
+                offset += size(skip);
+            }
+        }
+
+        readers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    fn inputs(rng: &mut ChaCha8Rng, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|_| rng.gen_range(-1.0..=1.0))
+            .collect()
+    }
+
+    mod prune {
+        use super::*;
+
+        #[test]
+        fn zeroes_small_weights() {
+            let mut network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 2,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                ],
+                vec![0.05, 0.5, -0.05]
+            );
+
+            network.prune(0.1);
+
+            assert_eq!(network.weights(), vec![0.05, 0.5, 0.0]);
+        }
+
+        #[test]
+        fn removes_unread_neurons() {
+            let mut network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 3,
+                        activation: Activation::Tanh,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                ],
+                vec![
+                    0.1, 0.5,
+                    0.2, -0.5,
+                    0.3, 0.8,
+                    0.4, 0.6, 0.01, -0.7
+                ]
+            );
+            let expected = 0.4 + 0.6 * (0.1_f32 + 0.25).tanh() - 0.7 * (0.3_f32 + 0.4).tanh();
+
+            network.prune(0.1);
+
+            assert_eq!(network.topology()[1].neurons, 2);
+            assert_eq!(network.weights(), vec![0.1, 0.5, 0.3, 0.8, 0.4, 0.6, -0.7]);
+            assert_relative_eq!(network.propagate(vec![0.5])[0], expected);
+        }
+
+        #[test]
+        fn folds_constant_neurons() {
+            let mut network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 2,
+                        activation: Activation::Sigmoid,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                ],
+                vec![
+                    0.1, 0.5,
+                    0.2, 0.01,
+                    0.4, 0.6, -0.7
+                ]
+            );
+            let constant = Activation::Sigmoid.apply(0.2);
+
+            network.prune(0.1);
+
+            assert_eq!(network.topology()[1].neurons, 1);
+            assert_relative_eq!(network.weights()[2], 0.4 - 0.7 * constant);
+        }
+
+        #[test]
+        fn keeps_recurrent_neurons() {
+            let mut network = Network::builder(1)
+                .recurrent_layer(2, Activation::Tanh)
+                .layer(1, Activation::Identity)
+                .build_from_weights(vec![
+                    0.1, 0.5, 0.0, 0.3,
+                    0.2, 0.0, 0.0, 0.0,
+                    0.4, 0.6, 0.0
+                ])
+                .unwrap();
+
+            network.prune(0.1);
+
+            assert_eq!(network.topology()[1].neurons, 2);
+        }
+
+        #[test]
+        fn matches_unpruned() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(4)
+                .recurrent_layer(6, Activation::Tanh)
+                .layer(5, Activation::ReLU)
+                .layer(3, Activation::Sigmoid)
+                .skip_from(0)
+                .skip_from(1)
+                .build_random(&mut rng)
+                .unwrap();
+
+            let mut pruned = Network::try_from_weights(&network.topology(), network.weights())
+                .unwrap();
+
+            // well past most weights, so that some neurons go away
+            pruned.prune(0.8);
+
+            // what `prune` zeroed, without removing anything
+            let mut zeroed = Network::try_from_weights(&network.topology(), network.weights())
+                .unwrap();
+
+            for neuron in zeroed.layers.iter_mut().flat_map(|layer| &mut layer.neurons) {
+                for weight in &mut neuron.weights {
+                    if weight.abs() < 0.8 {
+                        *weight = 0.0;
+                    }
+                }
+            }
+
+            assert!(pruned.topology().iter().map(|layer| layer.neurons).sum::<usize>()
+                < network.topology().iter().map(|layer| layer.neurons).sum::<usize>());
+
+            let mut state = zeroed.state();
+            let mut pruned_state = pruned.state();
+
+            for _ in 0..5 {
+                let inputs = inputs(&mut rng, 4);
+
+                let expected = zeroed.propagate_with_state(inputs.clone(), &mut state);
+                let actual = pruned.propagate_with_state(inputs, &mut pruned_state);
+
+                assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 1e-5);
+            }
+
+            // the topology still describes the weights
+            assert!(Network::try_from_weights(&pruned.topology(), pruned.weights()).is_ok());
+        }
+    }
+
+    mod stats {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 2,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 2,
+                        activation: Activation::ReLU,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                ],
+                vec![
+                    0.5, 1.0, 0.0,
+                    -2.0, 0.5, 0.5,
+                    0.0, 1.0, 0.0
+                ]
+            );
+
+            let stats = network.stats(&[vec![1.0, 1.0], vec![-1.0, 0.5]]);
+
+            assert_eq!(stats.connections, 4);
+            assert_eq!(stats.synapses, 6);
+            assert_relative_eq!(stats.sparsity, 1.0 / 3.0);
+            assert_eq!(stats.dead, vec![1, 0]);
+            assert_eq!(stats.histograms[0].min, -2.0);
+            assert_eq!(stats.histograms[0].max, 1.0);
+            assert_eq!(stats.histograms[0].bins.iter().sum::<usize>(), 6);
+        }
+    }
+
+    mod histogram {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let histogram = Histogram::new(&[0.0, 0.1, 0.5, 0.9, 1.0], 2);
+
+            assert_eq!(histogram, Histogram { min: 0.0, max: 1.0, bins: vec![2, 3] });
+        }
+
+        #[test]
+        fn single_value() {
+            let histogram = Histogram::new(&[0.3, 0.3], 4);
+
+            assert_eq!(histogram.bins, vec![2, 0, 0, 0]);
+        }
+    }
+}
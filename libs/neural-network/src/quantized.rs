@@ -0,0 +1,469 @@
+use super::*;
+
+// Affine mapping between `f32` and `i8`: real = scale * (quantized - zero_point)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantization {
+    pub scale: f32,
+    pub zero_point: i32,
+}
+
+impl Quantization {
+    // Covers `min..=max`, which is widened to include zero so that
+    // zero stays exact
+    fn asymmetric(min: f32, max: f32) -> Self {
+        let (min, max) = (min.min(0.0), max.max(0.0));
+
+        if max == min {
+            return Self { scale: 1.0, zero_point: 0 };
+        }
+
+        let scale = (max - min) / 255.0;
+        let zero_point = (-128.0 - min / scale).round() as i32;
+
+        Self { scale, zero_point }
+    }
+
+    // zero_point = 0, covers `-max_abs..=max_abs`
+    fn symmetric(max_abs: f32) -> Self {
+        let scale = if max_abs == 0.0 { 1.0 } else { max_abs / 127.0 };
+
+        Self { scale, zero_point: 0 }
+    }
+
+    fn quantize(&self, value: f32) -> i8 {
+        ((value / self.scale).round() as i32 + self.zero_point).clamp(-128, 127) as i8
+    }
+}
+
+// A `Network` frozen into 8-bit integer weights, for when inference
+// speed matters more than precision.
+//
+// Every layer keeps per-layer quantizations of its weights and of its
+// inputs, the latter calibrated from the input range seen over
+// a calibration set - inputs outside of it get clipped. Dot products
+// are integer-only, activations are still computed in `f32`.
+//
+// Like `MatrixNetwork`, it's stateless: recurrent layers always see
+// a zeroed state
+pub struct QuantizedNetwork {
+    layers: Vec<QuantizedLayer>,
+}
+
+struct QuantizedLayer {
+    inputs: usize,
+    // row-major, one row per neuron
+    weights: Vec<i8>,
+    // pre-scaled by `input.scale * weight.scale`; 64 bits, since tiny
+    // weights make for huge pre-scaled biases
+    biases: Vec<i64>,
+    input: Quantization,
+    weight: Quantization,
+    activation: Activation,
+    // see `LayerTopology::skips`
+    skips: Vec<usize>,
+}
+
+// Intermediate layer outputs and quantized inputs; reuse it between
+// calls to keep propagation allocation-free
+#[derive(Default)]
+pub struct QuantizedScratch {
+    // outputs of every hidden layer, back-to-back
+    outputs: Vec<f32>,
+    // inputs of layers with skip connections
+    gathered: Vec<f32>,
+    // inputs of the current layer, quantized
+    quantized: Vec<i32>,
+}
+
+impl QuantizedScratch {
+    fn reserve(&mut self, outputs: usize, inputs: usize) {
+        if self.outputs.len() < outputs {
+            self.outputs.resize(outputs, 0.0);
+        }
+
+        self.gathered.reserve(inputs);
+        self.quantized.reserve(inputs);
+    }
+}
+
+impl QuantizedLayer {
+    fn outputs(&self) -> usize {
+        self.biases.len()
+    }
+
+    fn propagate(&self, inputs: &[f32], quantized: &mut Vec<i32>, outputs: &mut [f32]) {
+        assert_eq!(inputs.len(), self.inputs);
+
+        // shifted by the zero point already, so that the dot product
+        // doesn't need to
+        quantized.clear();
+        quantized.extend(
+            inputs
+                .iter()
+                .map(|&input| self.input.quantize(input) as i32 - self.input.zero_point)
+        );
+
+        let scale = self.input.scale * self.weight.scale;
+
+        let neurons = outputs
+            .iter_mut()
+            .zip(self.weights.chunks_exact(self.inputs))
+            .zip(&self.biases);
+
+        for ((output, weights), &bias) in neurons {
+            // every product fits in `i32`, their sum might not
+            let sum = quantized
+                .iter()
+                .zip(weights)
+                .map(|(&input, &weight)| (input * weight as i32) as i64)
+                .sum::<i64>();
+
+            *output = self.activation.apply(bias.saturating_add(sum) as f32 * scale);
+        }
+    }
+}
+
+impl QuantizedNetwork {
+    // `calibration` should look like the inputs the network is going
+    // to see; check how much precision was lost with `max_error`
+    pub fn quantize(network: &Network, calibration: &[Vec<f32>]) -> Self {
+        assert!(!calibration.is_empty());
+
+        // input range of every layer, skip connections included
+        let mut ranges = vec![(f32::INFINITY, f32::NEG_INFINITY); network.layers.len()];
+
+        for sample in calibration {
+            let trace = network.propagate_traced(sample.clone());
+
+            let mut outputs = vec![trace.inputs];
+            outputs.extend(trace.layers.into_iter().map(|layer| layer.outputs));
+
+            for (idx, layer) in network.layers.iter().enumerate() {
+                let (min, max) = &mut ranges[idx];
+
+                for input in layer.gather(&outputs[..=idx]) {
+                    *min = min.min(input);
+                    *max = max.max(input);
+                }
+            }
+        }
+
+        let layers = network.layers
+            .iter()
+            .zip(ranges)
+            .map(|(layer, (min, max))| {
+                let inputs = layer.inputs();
+
+                // a zeroed state makes recurrent weights irrelevant
+                let weights: Vec<f32> = layer.neurons
+                    .iter()
+                    .flat_map(|neuron| neuron.weights[..inputs].iter().cloned())
+                    .collect();
+
+                let input = Quantization::asymmetric(min, max);
+                let weight = Quantization::symmetric(
+                    weights.iter().fold(0.0, |max, weight| weight.abs().max(max))
+                );
+
+                QuantizedLayer {
+                    inputs,
+                    weights: weights
+                        .iter()
+                        .map(|&value| weight.quantize(value))
+                        .collect(),
+                    biases: layer.neurons
+                        .iter()
+                        // `as` saturates, should the scales underflow to zero
+                        .map(|neuron| (neuron.bias / (input.scale * weight.scale)).round() as i64)
+                        .collect(),
+                    input,
+                    weight,
+                    activation: layer.activation,
+                    skips: layer.skips.clone(),
+                }
+            })
+            .collect();
+
+        Self { layers }
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.layers[0].inputs
+    }
+
+    // quantizations of every layer's (inputs, weights)
+    pub fn quantizations(&self) -> Vec<(Quantization, Quantization)> {
+        self.layers
+            .iter()
+            .map(|layer| (layer.input, layer.weight))
+            .collect()
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.layers[self.layers.len() - 1].outputs()
+    }
+
+    pub fn scratch(&self) -> QuantizedScratch {
+        let mut scratch = QuantizedScratch::default();
+        scratch.reserve(self.hidden(), self.widest_input());
+        scratch
+    }
+
+    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        let mut out = vec![0.0; self.outputs()];
+
+        self.propagate_into(&inputs, &mut self.scratch(), &mut out);
+        out
+    }
+
+    pub fn propagate_into(&self, input: &[f32], scratch: &mut QuantizedScratch, out: &mut [f32]) {
+        assert_eq!(input.len(), self.inputs());
+        assert_eq!(out.len(), self.outputs());
+
+        scratch.reserve(self.hidden(), self.widest_input());
+
+        let QuantizedScratch { outputs, gathered, quantized } = scratch;
+        let last = self.layers.len() - 1;
+        let mut offset = 0;
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            // every layer only reads outputs of the layers before it
+            let (done, rest) = outputs.split_at_mut(offset);
+
+            // outputs of layer `source`, in `LayerTopology` indexing
+            let source = |source: usize| -> &[f32] {
+                if source == 0 {
+                    input
+                } else {
+                    let start = self.hidden_before(source - 1);
+                    &done[start..start + self.layers[source - 1].outputs()]
+                }
+            };
+
+            let src = if layer.skips.is_empty() {
+                source(idx)
+            } else {
+                gathered.clear();
+
+                for &from in std::iter::once(&idx).chain(&layer.skips) {
+                    gathered.extend_from_slice(source(from));
+                }
+
+                &gathered[..]
+            };
+
+            let len = layer.outputs();
+
+            let dst = if idx == last {
+                &mut *out
+            } else {
+                &mut rest[..len]
+            };
+
+            layer.propagate(src, quantized, dst);
+
+            offset += len;
+        }
+    }
+
+    // Largest absolute difference between any output of this network
+    // and of `network` (stateless) over `inputs`
+    pub fn max_error(&self, network: &Network, inputs: &[Vec<f32>]) -> f32 {
+        let mut scratch = self.scratch();
+        let mut actual = vec![0.0; self.outputs()];
+
+        inputs
+            .iter()
+            .flat_map(|inputs| {
+                let expected = network.propagate(inputs.clone());
+
+                self.propagate_into(inputs, &mut scratch, &mut actual);
+
+                expected
+                    .into_iter()
+                    .zip(actual.clone())
+                    .map(|(expected, actual)| (expected - actual).abs())
+            })
+            .fold(0.0, f32::max)
+    }
+
+    // total size of all hidden layers' outputs
+    fn hidden(&self) -> usize {
+        self.hidden_before(self.layers.len() - 1)
+    }
+
+    // where the outputs of `layer` start in `QuantizedScratch::outputs`
+    fn hidden_before(&self, layer: usize) -> usize {
+        self.layers[..layer]
+            .iter()
+            .map(|layer| layer.outputs())
+            .sum()
+    }
+
+    fn widest_input(&self) -> usize {
+        self.layers
+            .iter()
+            .map(|layer| layer.inputs)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    fn samples(rng: &mut ChaCha8Rng, inputs: usize, count: usize) -> Vec<Vec<f32>> {
+        (0..count)
+            .map(|_| (0..inputs).map(|_| rng.gen_range(-1.0..=1.0)).collect())
+            .collect()
+    }
+
+    mod quantization {
+        use super::*;
+
+        #[test]
+        fn asymmetric() {
+            let quantization = Quantization::asymmetric(-1.0, 3.0);
+
+            assert_relative_eq!(quantization.scale, 4.0 / 255.0);
+            assert_eq!(quantization.zero_point, -64);
+            assert_eq!(quantization.quantize(0.0), -64);
+            assert_eq!(quantization.quantize(-1.0), -128);
+            assert_eq!(quantization.quantize(3.0), 127);
+            // clipped
+            assert_eq!(quantization.quantize(10.0), 127);
+        }
+
+        #[test]
+        fn keeps_zero() {
+            let quantization = Quantization::asymmetric(0.5, 1.0);
+
+            assert_eq!(quantization.quantize(0.0), -128);
+            assert_eq!(quantization.quantize(1.0), 127);
+        }
+
+        #[test]
+        fn symmetric() {
+            let quantization = Quantization::symmetric(0.5);
+
+            assert_eq!(quantization.zero_point, 0);
+            assert_eq!(quantization.quantize(0.5), 127);
+            assert_eq!(quantization.quantize(-0.5), -127);
+            assert_eq!(quantization.quantize(0.1), 25);
+        }
+    }
+
+    mod quantize {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 2,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                ],
+                vec![0.1, 0.5, -0.2]
+            );
+
+            let quantized = QuantizedNetwork::quantize(&network, &[vec![1.0, -1.0], vec![0.5, 0.0]]);
+            let (input, weight) = quantized.quantizations()[0];
+
+            assert_eq!(quantized.inputs(), 2);
+            assert_relative_eq!(input.scale, 2.0 / 255.0);
+            assert_relative_eq!(weight.scale, 0.5 / 127.0);
+            assert_eq!(quantized.layers[0].weights, vec![127, -51]);
+            assert_eq!(quantized.layers[0].biases, vec![(0.1 / (input.scale * weight.scale)).round() as i64]);
+        }
+
+        #[test]
+        fn close_to_network() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(6)
+                .recurrent_layer(10, Activation::ReLU)
+                .layer(6, Activation::Sigmoid)
+                .layer(2, Activation::Tanh)
+                .skip_from(0)
+                .build_random(&mut rng)
+                .unwrap();
+
+            let calibration = samples(&mut rng, 6, 100);
+            let quantized = QuantizedNetwork::quantize(&network, &calibration);
+
+            let test = samples(&mut rng, 6, 100);
+            let error = quantized.max_error(&network, &test);
+
+            assert!(error > 0.0);
+            assert!(error < 0.05, "error too large: {}", error);
+
+            for inputs in test {
+                let expected = network.propagate(inputs.clone());
+                let actual = quantized.propagate(inputs);
+
+                assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = error);
+            }
+        }
+
+        #[test]
+        fn tiny_weights() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 2,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Identity,
+                        ..Default::default()
+                    },
+                ],
+                vec![0.5, 1e-6, -2e-6]
+            );
+
+            let calibration = vec![vec![1.0, -1.0], vec![0.5, 0.0]];
+            let quantized = QuantizedNetwork::quantize(&network, &calibration);
+
+            // the pre-scaled bias is way past `i32`
+            assert!(quantized.layers[0].biases[0] > i32::MAX as i64);
+            assert!(quantized.max_error(&network, &calibration) < 1e-3);
+        }
+    }
+
+    mod propagate_into {
+        use super::*;
+
+        #[test]
+        fn matches_propagate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(4)
+                .layer(5, Activation::ReLU)
+                .layer(3, Activation::Sigmoid)
+                .layer(2, Activation::Tanh)
+                .skip_from(0)
+                .skip_from(1)
+                .build_random(&mut rng)
+                .unwrap();
+
+            let quantized = QuantizedNetwork::quantize(&network, &samples(&mut rng, 4, 20));
+            let mut scratch = quantized.scratch();
+            let mut out = vec![0.0; quantized.outputs()];
+
+            for inputs in samples(&mut rng, 4, 10) {
+                quantized.propagate_into(&inputs, &mut scratch, &mut out);
+
+                assert_eq!(out, quantized.propagate(inputs));
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Saliency {
+    // |d output / d input|, as a central difference with the given step
+    FiniteDifference { step: f32 },
+    // |change of output| when the input is replaced with `baseline`
+    Occlusion { baseline: f32 },
+}
+
+impl<F: Float> Network<F> {
+    // How much every output depends on every input, averaged over
+    // `samples`: `result[output][input]`, all values >= 0.
+    //
+    // Stateless, i.e. recurrent layers see a zeroed state
+    pub fn saliency(&self, samples: &[Vec<F>], method: Saliency) -> Vec<Vec<F>> {
+        let inputs = self.inputs();
+        let outputs = self.layers[self.layers.len() - 1].neurons.len();
+
+        let mut saliency = vec![vec![F::zero(); inputs]; outputs];
+
+        if samples.is_empty() {
+            return saliency;
+        }
+
+        for sample in samples {
+            assert_eq!(sample.len(), inputs);
+
+            let expected = match method {
+                Saliency::FiniteDifference { .. } => Vec::new(),
+                Saliency::Occlusion { .. } => self.propagate(sample.clone()),
+            };
+
+            for input in 0..inputs {
+                let changed = |value: F| {
+                    let mut sample = sample.clone();
+                    sample[input] = value;
+                    self.propagate(sample)
+                };
+
+                let deltas: Vec<F> = match method {
+                    Saliency::FiniteDifference { step } => {
+                        let step = F::from_f32(step);
+                        let two = F::from_f32(2.0);
+
+                        changed(sample[input] + step)
+                            .into_iter()
+                            .zip(changed(sample[input] - step))
+                            .map(|(above, below)| (above - below) / (two * step))
+                            .collect()
+                    }
+                    Saliency::Occlusion { baseline } => changed(F::from_f32(baseline))
+                        .into_iter()
+                        .zip(&expected)
+                        .map(|(occluded, &expected)| expected - occluded)
+                        .collect(),
+                };
+
+                for (row, delta) in saliency.iter_mut().zip(deltas) {
+                    row[input] = row[input] + delta.abs();
+                }
+            }
+        }
+
+        let count = F::from_f64(samples.len() as f64);
+
+        for value in saliency.iter_mut().flatten() {
+            *value = *value / count;
+        }
+
+        saliency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    // out 0 = relu(2 * in 0 - in 1), out 1 = relu(in 2)
+    fn network() -> Network {
+        Network::from_weights(
+            &[
+                LayerTopology {
+                    neurons: 3,
+                    ..Default::default()
+                },
+                LayerTopology {
+                    neurons: 2,
+                    ..Default::default()
+                },
+            ],
+            vec![
+                0.0, 2.0, -1.0, 0.0,
+                0.0, 0.0, 0.0, 1.0
+            ]
+        )
+    }
+
+    mod saliency {
+        use super::*;
+
+        #[test]
+        fn finite_difference() {
+            let saliency = network().saliency(
+                &[vec![0.5, 0.5, 0.5], vec![0.5, 0.5, -0.5]],
+                Saliency::FiniteDifference { step: 0.01 }
+            );
+
+            assert_relative_eq!(saliency[0].as_slice(), [2.0, 1.0, 0.0].as_ref(), epsilon = 1e-3);
+            // relu is flat for the second sample
+            assert_relative_eq!(saliency[1].as_slice(), [0.0, 0.0, 0.5].as_ref(), epsilon = 1e-3);
+        }
+
+        #[test]
+        fn occlusion() {
+            let saliency = network().saliency(
+                &[vec![1.0, 0.5, 0.25], vec![-1.0, 0.0, 0.75]],
+                Saliency::Occlusion { baseline: 0.0 }
+            );
+
+            assert_relative_eq!(saliency[0].as_slice(), [0.75, 0.25, 0.0].as_ref());
+            assert_relative_eq!(saliency[1].as_slice(), [0.0, 0.0, 0.5].as_ref());
+        }
+
+        #[test]
+        fn no_samples() {
+            let saliency = network().saliency(&[], Saliency::Occlusion { baseline: 0.0 });
+
+            assert_eq!(saliency, vec![vec![0.0; 3]; 2]);
+        }
+    }
+}
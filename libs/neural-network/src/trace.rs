@@ -0,0 +1,134 @@
+use super::*;
+
+#[derive(Clone, Debug)]
+pub struct LayerTrace<F = f32> {
+    // pre-activation outputs (bias included)
+    pub sums: Vec<F>,
+    pub outputs: Vec<F>,
+}
+
+// What every neuron did during a single propagation
+#[derive(Clone, Debug)]
+pub struct Trace<F = f32> {
+    pub inputs: Vec<F>,
+    // one entry per non-input layer
+    pub layers: Vec<LayerTrace<F>>,
+}
+
+impl<F> Trace<F> {
+    pub fn outputs(&self) -> &[F] {
+        &self.layers[self.layers.len() - 1].outputs
+    }
+}
+
+impl<F: Float> Network<F> {
+    // Traced counterpart of `propagate`
+    pub fn propagate_traced(&self, inputs: Vec<F>) -> Trace<F> {
+        self.propagate_traced_with_state(inputs, &mut self.state())
+    }
+
+    // Traced counterpart of `propagate_with_state`
+    pub fn propagate_traced_with_state(
+        &self,
+        inputs: Vec<F>,
+        state: &mut NetworkState<F>
+    ) -> Trace<F> {
+        assert_eq!(state.layers.len(), self.layers.len());
+
+        let mut layers: Vec<LayerTrace<F>> = Vec::with_capacity(self.layers.len());
+        let mut outputs = vec![inputs.clone()];
+
+        for (layer, state) in self.layers.iter().zip(&mut state.layers) {
+            let trace = layer.trace_with_state(layer.gather(&outputs), state);
+
+            outputs.push(trace.outputs.clone());
+            layers.push(trace);
+        }
+
+        Trace { inputs, layers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    mod propagate_traced {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 2,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 2,
+                        activation: Activation::ReLU,
+                        recurrent: false,
+                        skips: Vec::new(),
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Tanh,
+                        recurrent: false,
+                        skips: Vec::new(),
+                    },
+                ],
+                vec![
+                    0.1, 0.5, -0.5,
+                    -0.2, -1.0, 0.25,
+                    0.3, 0.8, -0.6
+                ]
+            );
+
+            let trace = network
+                .propagate_traced(vec![0.4, 0.2]);
+
+            let sums: Vec<f32> = vec![
+                0.1 + (0.5 * 0.4) + (-0.5 * 0.2),
+                -0.2 - 0.4 + (0.25 * 0.2)
+            ];
+            let outputs = vec![sums[0], 0.0];
+            let sum = 0.3 + (0.8 * outputs[0]) + (-0.6 * outputs[1]);
+
+            assert_eq!(trace.inputs, vec![0.4, 0.2]);
+            assert_eq!(trace.layers.len(), 2);
+            assert_relative_eq!(trace.layers[0].sums.as_slice(), sums.as_slice());
+            assert_relative_eq!(trace.layers[0].outputs.as_slice(), outputs.as_slice());
+            assert_relative_eq!(trace.layers[1].sums[0], sum);
+            assert_relative_eq!(trace.outputs()[0], sum.tanh());
+        }
+
+        #[test]
+        fn matches_propagate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(4)
+                .recurrent_layer(6, Activation::Sigmoid)
+                .layer(2, Activation::Softsign)
+                .build_random(&mut rng)
+                .unwrap();
+
+            let mut state = network.state();
+            let mut traced_state = network.state();
+
+            for _ in 0..3 {
+                let inputs: Vec<f32> = (0..4)
+                    .map(|_| rng.gen_range(-1.0..=1.0))
+                    .collect();
+
+                let expected = network
+                    .propagate_with_state(inputs.clone(), &mut state);
+                let trace = network
+                    .propagate_traced_with_state(inputs, &mut traced_state);
+
+                assert_eq!(trace.outputs(), expected.as_slice());
+            }
+        }
+    }
+}
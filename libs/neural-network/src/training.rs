@@ -0,0 +1,514 @@
+use super::*;
+
+// A single supervised example: network input and the output we want
+pub type Sample<F = f32> = (Vec<F>, Vec<F>);
+
+#[derive(Clone, Copy, Debug)]
+pub enum Optimizer {
+    Sgd {
+        learning_rate: f32,
+    },
+    Adam {
+        learning_rate: f32,
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+    },
+}
+
+impl Optimizer {
+    pub fn sgd(learning_rate: f32) -> Self {
+        Self::Sgd { learning_rate }
+    }
+
+    // with the usual defaults from the Adam paper
+    pub fn adam(learning_rate: f32) -> Self {
+        Self::Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+}
+
+// Trains a network with backpropagation and MSE loss.
+//
+// Training goes through the stateless `Network::propagate`, so recurrent
+// layers always see a zeroed state and their recurrent weights receive
+// no gradient.
+//
+// Samples are visited in the given order - shuffle them between epochs
+// if that matters.
+pub struct Trainer {
+    optimizer: Optimizer,
+    batch_size: usize,
+    // Adam's first and second moment estimates, one per weight;
+    // kept as `f64` so that one trainer fits every scalar type
+    moments: Vec<(f64, f64)>,
+    steps: i32,
+}
+
+impl Trainer {
+    pub fn new(optimizer: Optimizer) -> Self {
+        Self {
+            optimizer,
+            batch_size: 1,
+            moments: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0);
+
+        self.batch_size = batch_size;
+        self
+    }
+
+    // One pass over `samples`; returns the mean loss seen during the pass
+    pub fn train_epoch<F: Float>(&mut self, network: &mut Network<F>, samples: &[Sample<F>]) -> F {
+        if samples.is_empty() {
+            return F::zero();
+        }
+
+        let loss: F = samples
+            .chunks(self.batch_size)
+            .map(|batch| self.train_batch(network, batch) * count(batch.len()))
+            .sum();
+
+        loss / count(samples.len())
+    }
+
+    // A single optimizer step over `batch`; returns the batch's mean loss
+    // from before the step
+    pub fn train_batch<F: Float>(&mut self, network: &mut Network<F>, batch: &[Sample<F>]) -> F {
+        if batch.is_empty() {
+            return F::zero();
+        }
+
+        let mut gradient = vec![F::zero(); network.weights_count()];
+        let mut loss = F::zero();
+
+        for (input, target) in batch {
+            loss = loss + network.backpropagate(input, target, &mut gradient);
+        }
+
+        let scale = F::one() / count(batch.len());
+
+        for grad in &mut gradient {
+            *grad = *grad * scale;
+        }
+
+        self.step(network, &gradient);
+
+        loss * scale
+    }
+
+    fn step<F: Float>(&mut self, network: &mut Network<F>, gradient: &[F]) {
+        match self.optimizer {
+            Optimizer::Sgd { learning_rate } => {
+                let learning_rate = F::from_f32(learning_rate);
+
+                for (weight, &grad) in network.weights_mut().zip(gradient) {
+                    *weight = *weight - learning_rate * grad;
+                }
+            }
+
+            Optimizer::Adam { learning_rate, beta1, beta2, epsilon } => {
+                if self.moments.len() != gradient.len() {
+                    self.moments = vec![(0.0, 0.0); gradient.len()];
+                    self.steps = 0;
+                }
+
+                self.steps += 1;
+
+                let (beta1, beta2) = (beta1 as f64, beta2 as f64);
+                let bias1 = 1.0 - beta1.powi(self.steps);
+                let bias2 = 1.0 - beta2.powi(self.steps);
+
+                let params = network
+                    .weights_mut()
+                    .zip(gradient)
+                    .zip(&mut self.moments);
+
+                for ((weight, &grad), (m, v)) in params {
+                    let grad = grad.to_f64();
+
+                    *m = beta1 * *m + (1.0 - beta1) * grad;
+                    *v = beta2 * *v + (1.0 - beta2) * grad * grad;
+
+                    let delta = learning_rate as f64 * (*m / bias1)
+                        / ((*v / bias2).sqrt() + epsilon as f64);
+
+                    *weight = *weight - F::from_f64(delta);
+                }
+            }
+        }
+    }
+}
+
+// mean squared error
+pub fn mse<F: Float>(outputs: &[F], targets: &[F]) -> F {
+    assert_eq!(outputs.len(), targets.len());
+
+    outputs
+        .iter()
+        .zip(targets)
+        .map(|(&output, &target)| (output - target).powi(2))
+        .sum::<F>()
+        / count(outputs.len())
+}
+
+fn count<F: Float>(len: usize) -> F {
+    F::from_f64(len as f64)
+}
+
+impl<F: Float> Network<F> {
+    // mean loss over `samples`
+    pub fn loss(&self, samples: &[Sample<F>]) -> F {
+        if samples.is_empty() {
+            return F::zero();
+        }
+
+        samples
+            .iter()
+            .map(|(input, target)| mse(&self.propagate(input.clone()), target))
+            .sum::<F>()
+            / count(samples.len())
+    }
+
+    // Adds d(loss)/d(weight) for a single sample to `gradient`, which is
+    // laid out the same way as `weights()`; returns the sample's loss
+    pub(crate) fn backpropagate(&self, input: &[F], target: &[F], gradient: &mut [F]) -> F {
+        let trace = self.propagate_traced(input.to_vec());
+        let outputs = trace.outputs();
+
+        assert_eq!(target.len(), outputs.len());
+
+        let last = self.layers.len() - 1;
+        let scale = F::from_f32(2.0) / count(outputs.len());
+
+        // outputs of every layer, network inputs first
+        let history: Vec<Vec<F>> = std::iter::once(trace.inputs.clone())
+            .chain(trace.layers.iter().map(|layer| layer.outputs.clone()))
+            .collect();
+
+        // d(loss)/d(output) of every layer's neurons, filled in
+        // by the layers they feed into
+        let mut errors: Vec<Vec<F>> = self.layers
+            .iter()
+            .map(|layer| vec![F::zero(); layer.neurons.len()])
+            .collect();
+
+        errors[last] = outputs
+            .iter()
+            .zip(target)
+            .map(|(&output, &target)| scale * (output - target))
+            .collect();
+
+        let mut offset = gradient.len();
+
+        for (idx, layer) in self.layers.iter().enumerate().rev() {
+            let layer_inputs = layer.gather(&history[..=idx]);
+
+            // d(loss)/d(sum)
+            let deltas: Vec<F> = errors[idx]
+                .iter()
+                .zip(&trace.layers[idx].sums)
+                .map(|(&error, &sum)| error * layer.activation.eval_derivative(sum))
+                .collect();
+
+            offset -= layer.neurons.len() * (1 + layer.neurons[0].weights.len());
+
+            let mut cursor = offset;
+
+            for (neuron, &delta) in layer.neurons.iter().zip(&deltas) {
+                gradient[cursor] = gradient[cursor] + delta;
+
+                // recurrent weights are multiplied by a zeroed state,
+                // so their gradient is zero as well
+                for (grad, &input) in gradient[cursor + 1..].iter_mut().zip(&layer_inputs) {
+                    *grad = *grad + delta * input;
+                }
+
+                cursor += 1 + neuron.weights.len();
+            }
+
+            // same order as in `Layer::gather`
+            let sources = std::iter::once(idx).chain(layer.skips.iter().cloned());
+            let mut input = 0;
+
+            for source in sources {
+                let len = history[source].len();
+
+                // source 0 is the network's input, which has nothing to learn
+                if source > 0 {
+                    for (k, error) in errors[source - 1].iter_mut().enumerate() {
+                        *error = *error + layer.neurons
+                            .iter()
+                            .zip(&deltas)
+                            .map(|(neuron, &delta)| delta * neuron.weights[input + k])
+                            .sum::<F>();
+                    }
+                }
+
+                input += len;
+            }
+        }
+
+        mse(outputs, target)
+    }
+
+    pub(crate) fn weights_count(&self) -> usize {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .map(|neuron| 1 + neuron.weights.len())
+            .sum()
+    }
+
+    // same order as `weights()`
+    pub(crate) fn weights_mut(&mut self) -> impl Iterator<Item = &mut F> {
+        use std::iter::once;
+
+        self.layers
+            .iter_mut()
+            .flat_map(|layer| layer.neurons.iter_mut())
+            .flat_map(|neuron| once(&mut neuron.bias).chain(&mut neuron.weights))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use approx::assert_relative_eq;
+
+    fn samples(rng: &mut dyn rand::RngCore, count: usize) -> Vec<Sample> {
+        // a smooth, non-linear target the networks below can represent
+        (0..count)
+            .map(|_| {
+                let input: Vec<f32> = vec![rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)];
+                let target = vec![(input[0] - 0.5 * input[1]).tanh()];
+
+                (input, target)
+            })
+            .collect()
+    }
+
+    mod mse {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_relative_eq!(mse(&[1.0, 2.0], &[1.0, 2.0]), 0.0);
+            assert_relative_eq!(mse(&[1.0, -1.0], &[0.0, 1.0]), 2.5);
+        }
+    }
+
+    mod backpropagate {
+        use super::*;
+
+        fn assert_matches_finite_difference(network: &Network, input: &[f32], target: &[f32]) {
+            let mut gradient = vec![0.0; network.weights_count()];
+            network.backpropagate(input, target, &mut gradient);
+
+            let weights = network.weights();
+            let topology = network.topology();
+            let h = 1e-2;
+
+            for (idx, grad) in gradient.iter().enumerate() {
+                let loss = |delta: f32| {
+                    let mut weights = weights.clone();
+                    weights[idx] += delta;
+
+                    let network = Network::from_weights(&topology, weights);
+                    mse(&network.propagate(input.to_vec()), target)
+                };
+
+                let expected = (loss(h) - loss(-h)) / (2.0 * h);
+
+                assert_relative_eq!(*grad, expected, epsilon = 1e-3);
+            }
+        }
+
+        #[test]
+        fn matches_finite_difference() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(3)
+                .layer(4, Activation::Sigmoid)
+                .recurrent_layer(3, Activation::Tanh)
+                .layer(2, Activation::Softsign)
+                .build_random(&mut rng)
+                .unwrap();
+
+            assert_matches_finite_difference(&network, &[0.3, -0.8, 0.5], &[0.25, -0.4]);
+        }
+
+        #[test]
+        fn skips() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(3)
+                .layer(4, Activation::Sigmoid)
+                .layer(3, Activation::Tanh)
+                .layer(2, Activation::Softsign)
+                .skip_from(0)
+                .skip_from(1)
+                .build_random(&mut rng)
+                .unwrap();
+
+            assert_matches_finite_difference(&network, &[0.3, -0.8, 0.5], &[0.25, -0.4]);
+        }
+
+        #[test]
+        fn f64() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::builder(2)
+                .layer(3, Activation::Tanh)
+                .layer(1, Activation::Sigmoid)
+                .scalar::<f64>()
+                .build_random(&mut rng)
+                .unwrap();
+
+            let input = vec![0.3, -0.8];
+            let target = vec![0.25];
+
+            let mut gradient = vec![0.0; network.weights_count()];
+            network.backpropagate(&input, &target, &mut gradient);
+
+            let weights = network.weights();
+            let topology = network.topology();
+            let h = 1e-6;
+
+            for (idx, grad) in gradient.iter().enumerate() {
+                let loss = |delta: f64| {
+                    let mut weights = weights.clone();
+                    weights[idx] += delta;
+
+                    let network = NetworkBuilder::from_topology(&topology)
+                        .scalar::<f64>()
+                        .build_from_weights(weights)
+                        .unwrap();
+
+                    mse(&network.propagate(input.clone()), &target)
+                };
+
+                let expected = (loss(h) - loss(-h)) / (2.0 * h);
+
+                // much tighter than what `f32` can manage
+                assert_relative_eq!(*grad, expected, epsilon = 1e-8);
+            }
+        }
+
+        #[test]
+        fn returns_loss() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Identity,
+                        recurrent: false,
+                        skips: Vec::new(),
+                    },
+                ],
+                vec![0.5, 2.0]
+            );
+
+            let mut gradient = vec![0.0; 2];
+            let loss = network.backpropagate(&[1.0], &[1.5], &mut gradient);
+
+            // output = 0.5 + 2.0 * 1.0 = 2.5
+            assert_relative_eq!(loss, 1.0);
+            assert_relative_eq!(gradient[0], 2.0);
+            assert_relative_eq!(gradient[1], 2.0);
+        }
+    }
+
+    mod trainer {
+        use super::*;
+
+        fn network(rng: &mut dyn rand::RngCore) -> Network {
+            Network::builder(2)
+                .layer(6, Activation::Tanh)
+                .layer(1, Activation::Identity)
+                .build_random(rng)
+                .unwrap()
+        }
+
+        fn train(optimizer: Optimizer, batch_size: usize, epochs: usize) -> (f32, f32) {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut network = network(&mut rng);
+            let samples = samples(&mut rng, 64);
+            let mut trainer = Trainer::new(optimizer).batch_size(batch_size);
+
+            let before = network.loss(&samples);
+
+            for _ in 0..epochs {
+                trainer.train_epoch(&mut network, &samples);
+            }
+
+            (before, network.loss(&samples))
+        }
+
+        #[test]
+        fn sgd() {
+            let (before, after) = train(Optimizer::sgd(0.1), 1, 50);
+
+            assert!(after < 0.1 * before, "{} -> {}", before, after);
+        }
+
+        #[test]
+        fn sgd_mini_batch() {
+            let (before, after) = train(Optimizer::sgd(0.5), 8, 200);
+
+            assert!(after < 0.1 * before, "{} -> {}", before, after);
+        }
+
+        #[test]
+        fn adam() {
+            let (before, after) = train(Optimizer::adam(0.01), 8, 100);
+
+            assert!(after < 0.1 * before, "{} -> {}", before, after);
+        }
+
+        #[test]
+        fn full_batch_step() {
+            let mut network = Network::from_weights(
+                &[
+                    LayerTopology {
+                        neurons: 1,
+                        ..Default::default()
+                    },
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Identity,
+                        recurrent: false,
+                        skips: Vec::new(),
+                    },
+                ],
+                vec![0.0, 1.0]
+            );
+
+            let samples = vec![
+                (vec![1.0], vec![2.0]),
+                (vec![-1.0], vec![0.0]),
+            ];
+
+            let loss = Trainer::new(Optimizer::sgd(0.5))
+                .batch_size(2)
+                .train_batch(&mut network, &samples);
+
+            // both samples miss by exactly -1.0, so only the bias moves
+            assert_relative_eq!(loss, 1.0);
+            let weights = network.weights();
+
+            assert_relative_eq!(weights.as_slice(), [1.0, 1.0].as_ref());
+        }
+    }
+}
@@ -3,6 +3,7 @@ use rand::prelude::*;
 use wasm_bindgen::prelude::*;
 
 mod world;
+mod trace;
 
 #[wasm_bindgen]
 pub struct Simulation {
@@ -37,6 +38,22 @@ impl Simulation {
             .unwrap()
     }
 
+    // `null` if there's no such animal
+    pub fn trace(&self, animal: usize) -> JsValue {
+        let trace = self.sim
+            .trace(animal)
+            .map(|trace| trace::Trace::from(&trace));
+
+        JsValue::from_serde(&trace)
+            .unwrap()
+    }
+
+    // SVG markup, `undefined` if there's no such animal
+    pub fn diagram(&self, animal: usize) -> Option<String> {
+        self.sim
+            .diagram(animal)
+    }
+
     pub fn step(&mut self) {
         self.sim
             .step(&mut self.rng);
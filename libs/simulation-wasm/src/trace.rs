@@ -0,0 +1,38 @@
+use lib_simulation as sim;
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Trace {
+    pub(crate) inputs: Vec<f32>,
+    pub(crate) layers: Vec<Layer>
+}
+
+impl From<&sim::Trace> for Trace {
+    fn from(trace: &sim::Trace) -> Self {
+        let layers = trace
+            .layers
+            .iter()
+            .map(Layer::from)
+            .collect();
+
+        Self {
+            inputs: trace.inputs.clone(),
+            layers
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Layer {
+    pub(crate) sums: Vec<f32>,
+    pub(crate) outputs: Vec<f32>
+}
+
+impl From<&sim::LayerTrace> for Layer {
+    fn from(layer: &sim::LayerTrace) -> Self {
+        Self {
+            sums: layer.sums.clone(),
+            outputs: layer.outputs.clone()
+        }
+    }
+}
@@ -3,54 +3,247 @@ use lib_genetic_algorithm as ga;
 use rand::RngCore;
 
 use super::eye;
+use super::{ACTIONS, ACTION_NAMES};
+
+// Plastic weights are kept within +-this, so that they can't grow
+// without bounds over a long generation
+const PLASTIC_WEIGHT_LIMIT: f32 = 4.0;
+
+// How brains are built, the same for every animal in a simulation
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BrainConfig {
+    pub head: nn::OutputHead,
+    // lets brains keep learning while they live; the rule's coefficients
+    // are evolved along with the weights
+    pub plasticity: Option<nn::HebbianRule>,
+}
 
 pub struct Brain {
     pub(crate) neural_network: nn::Network,
+    // memory of the recurrent layer(s), carried between steps
+    pub(crate) state: nn::NetworkState,
+    pub(crate) head: nn::OutputHead,
+    pub(crate) plasticity: Option<nn::Plasticity>,
+    // weights the brain was born with; plasticity only changes
+    // the network's, so what was learned isn't inherited
+    pub(crate) weights: Vec<f32>,
 }
 
 impl Brain {
-    pub fn randomize(rng: &mut dyn RngCore, eye: &eye::Eye) -> Self {
+    pub fn randomize(
+        rng: &mut dyn RngCore,
+        eye: &eye::Eye,
+        config: BrainConfig
+    ) -> Self {
+        let neural_network = nn::Network::randomize(&Self::topology(eye, config.head), rng);
+
+        let plasticity = config.plasticity
+            .map(|rule| nn::Plasticity::randomize(rule, &neural_network, rng));
+
+        Self::new(neural_network, config.head, plasticity)
+    }
+
+    fn new(
+        neural_network: nn::Network,
+        head: nn::OutputHead,
+        plasticity: Option<nn::Plasticity>
+    ) -> Self {
+        let state = neural_network.state();
+        let weights = neural_network.weights();
+
         Self {
-            neural_network: nn::Network::randomize(&Self::topology(eye), rng)
+            neural_network,
+            state,
+            head,
+            plasticity: plasticity.map(|plasticity| plasticity.limit(PLASTIC_WEIGHT_LIMIT)),
+            weights
+        }
+    }
+
+    pub fn propagate(&mut self, vision: Vec<f32>) -> Vec<f32> {
+        match &self.plasticity {
+            Some(plasticity) => self.neural_network
+                .propagate_plastic(vision, &mut self.state, plasticity)
+                .expect("plasticity is built for this network"),
+            None => self.neural_network
+                .propagate_with_state(vision, &mut self.state),
         }
     }
+
+    pub fn decide(&mut self, vision: Vec<f32>, rng: &mut dyn RngCore) -> nn::Decision {
+        let outputs = self.propagate(vision);
+
+        self.head
+            .decide(outputs, rng)
+    }
+
+    // what the brain would do with `vision`, without touching its memory
+    pub fn trace(&self, vision: Vec<f32>) -> nn::Trace {
+        self.neural_network
+            .propagate_traced_with_state(vision, &mut self.state.clone())
+    }
+
+    pub fn diagram(&self, eye: &eye::Eye) -> String {
+        let outputs: &[&str] = if self.head.is_categorical() {
+            &ACTION_NAMES
+        } else {
+            &["speed", "rotation"]
+        };
+
+        let labels = nn::Labels::new(
+            (0..eye.photoreceptors()).map(|cell| format!("photoreceptor {}", cell)),
+            outputs.iter().cloned()
+        );
+
+        self.neural_network
+            .to_svg(&labels)
+    }
     
+    // weights, followed by the plasticity coefficients, if any
     pub fn as_chromosome(&self) -> ga::individual::Chromosome {
-        ga::individual::Chromosome {
-            genes: self.neural_network
-                .weights()
-        }
+        let coefficients = self.plasticity
+            .iter()
+            .flat_map(|plasticity| plasticity.coefficients());
+
+        self.weights
+            .iter()
+            .chain(coefficients)
+            .cloned()
+            .collect()
     }
 
     pub fn from_chromosome(
         chromosome: ga::individual::Chromosome,
-        eye: &eye::Eye
-    ) -> Self {
-        Self {
-            neural_network: nn::Network::from_weights(
-                &Self::topology(&eye),
-                chromosome
-            )
+        eye: &eye::Eye,
+        config: BrainConfig
+    ) -> Result<Self, nn::NetworkError> {
+        let topology = Self::topology(eye, config.head);
+        let weights = nn::NetworkBuilder::from_topology(&topology)
+            .weights_count()?;
+
+        let actual = chromosome.len();
+        let mut genes = chromosome.into_iter();
+
+        let neural_network = nn::Network::try_from_weights(
+            &topology,
+            genes.by_ref().take(weights)
+        )?;
+
+        let plasticity = config.plasticity
+            .map(|rule| {
+                let coefficients = nn::Plasticity::coefficients_count(rule, &neural_network);
+
+                nn::Plasticity::from_coefficients(
+                    rule,
+                    &neural_network,
+                    genes.by_ref().take(coefficients)
+                )
+            })
+            .transpose()?;
+
+        if genes.next().is_some() {
+            let coefficients = plasticity
+                .as_ref()
+                .map_or(0, |plasticity| plasticity.coefficients().len());
+
+            return Err(nn::NetworkError::TooManyWeights {
+                expected: weights + coefficients,
+                actual,
+            });
         }
+
+        Ok(Self::new(neural_network, config.head, plasticity))
     }
 
-    pub fn topology(eye: &eye::Eye) -> [nn::LayerTopology; 3] {
+    pub fn topology(eye: &eye::Eye, head: nn::OutputHead) -> [nn::LayerTopology; 3] {
+        // one logit per action for the categorical heads
+        let (outputs, activation) = if head.is_categorical() {
+            (ACTIONS.len(), nn::Activation::Identity)
+        } else {
+            (2, nn::Activation::Tanh)
+        };
+
         [
         // input layer
         // neuron for each photoreceptor
         nn::LayerTopology {
             neurons: eye.photoreceptors(),
+            activation: nn::Activation::Identity,
+            recurrent: false,
+            skips: Vec::new(),
         },
         // hidden layer(s)
         // Trial #1: 2x input layer's neurons
+        // recurrent, so that food doesn't vanish from the animal's mind
+        // the moment it leaves the field of view
         nn::LayerTopology {
             neurons: 2 * eye.photoreceptors(),
+            activation: nn::Activation::ReLU,
+            recurrent: true,
+            skips: Vec::new(),
         },
         // output layer
-        // two neurons, one for speed, other for rotation/direction
+        // continuous: two neurons, one for speed, other for rotation/direction
+        // tanh lets the animal slow down and turn both ways
         nn::LayerTopology {
-            neurons: 2,
+            neurons: outputs,
+            activation,
+            recurrent: false,
+            skips: Vec::new(),
         }
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    mod from_chromosome {
+        use super::*;
+
+        fn round_trip(config: BrainConfig, extra: usize) -> Result<Brain, nn::NetworkError> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let eye = eye::Eye::default();
+            let mut chromosome = Brain::randomize(&mut rng, &eye, config).as_chromosome();
+
+            chromosome.genes.extend(vec![0.0; extra]);
+
+            Brain::from_chromosome(chromosome, &eye, config)
+        }
+
+        #[test]
+        fn test() {
+            let config = BrainConfig::default();
+
+            assert!(round_trip(config, 0).is_ok());
+        }
+
+        #[test]
+        fn too_long() {
+            let config = BrainConfig::default();
+            let expected = round_trip(config, 0).unwrap().as_chromosome().len();
+
+            assert_eq!(
+                round_trip(config, 1).err(),
+                Some(nn::NetworkError::TooManyWeights { expected, actual: expected + 1 })
+            );
+        }
+
+        #[test]
+        fn too_long_with_plasticity() {
+            let config = BrainConfig {
+                plasticity: Some(nn::HebbianRule::Oja),
+                ..Default::default()
+            };
+            let expected = round_trip(config, 0).unwrap().as_chromosome().len();
+
+            assert_eq!(
+                round_trip(config, 2).err(),
+                Some(nn::NetworkError::TooManyWeights { expected, actual: expected + 2 })
+            );
+        }
+    }
+}
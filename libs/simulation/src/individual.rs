@@ -1,6 +1,7 @@
 use lib_genetic_algorithm as ga;
 use rand::RngCore;
 
+use super::brain;
 use super::world;
 
 pub struct AnimalIndividual {
@@ -16,8 +17,8 @@ impl AnimalIndividual {
         }
     }
     
-    pub fn as_animal(self, rng: &mut dyn RngCore) -> world::Animal {
-        world::Animal::from_chromosome(self.chromosome, rng)
+    pub fn as_animal(self, config: brain::BrainConfig, rng: &mut dyn RngCore) -> world::Animal {
+        world::Animal::from_chromosome(self.chromosome, config, rng)
     }
 }
 
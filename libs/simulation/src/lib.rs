@@ -1,9 +1,13 @@
 use rand::{Rng, RngCore};
 use nalgebra as na;
 use lib_genetic_algorithm as ga;
+use lib_neural_network as nn;
 use std::f32::consts::FRAC_PI_8;
 
 pub mod world;
+pub use nn::{HebbianRule, LayerTrace, OutputHead, Saliency, Trace};
+pub use brain::BrainConfig;
+pub use ga::hall_of_fame::HallOfFame;
 mod eye;
 mod brain;
 mod individual;
@@ -13,6 +17,40 @@ const MIN_SPEED: f32 = 0.001;
 const MAX_SPEED: f32 = 0.005;
 const LIN_ACCELERATION: f32 = 0.2;
 const ROT_ACCELERATION: f32 = FRAC_PI_8;
+// how many of the best animals of all time are remembered
+const HALL_OF_FAME_SIZE: usize = 10;
+
+// What animals with a categorical output head can choose from,
+// as (change in speed, change in direction)
+pub(crate) const ACTIONS: [(f32, f32); 4] = [
+    // go straight, speeding up
+    (LIN_ACCELERATION, 0.0),
+    // slow down
+    (-LIN_ACCELERATION, 0.0),
+    // turn left
+    (0.0, ROT_ACCELERATION),
+    // turn right
+    (0.0, -ROT_ACCELERATION),
+];
+
+// what `ACTIONS` are called in brain diagrams
+pub(crate) const ACTION_NAMES: [&str; 4] = [
+    "speed up",
+    "slow down",
+    "turn left",
+    "turn right",
+];
+
+// How different the animals' brains are from one another,
+// as mean distances over all pairs of animals
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Diversity {
+    // euclidean distance between the weights
+    pub weights: f32,
+    // euclidean distance between the outputs, over what
+    // every animal currently sees
+    pub behaviour: f32,
+}
 
 pub struct Simulation {
     world: world::World,
@@ -21,6 +59,11 @@ pub struct Simulation {
         ga::crossover::UniformCrossover>,
     age: usize,
     generation_length: usize,
+    // how many generations have evolved so far
+    generation: usize,
+    hall_of_fame: ga::hall_of_fame::HallOfFame,
+    // how every animal's brain is built
+    brain: brain::BrainConfig,
 }
 
 impl Simulation {
@@ -29,28 +72,135 @@ impl Simulation {
         generation_length: usize,
         animals: usize,
         foods: usize,
+    ) -> Self {
+        Self::random_with_brain(rng, generation_length, animals, foods, brain::BrainConfig::default())
+    }
+
+    pub fn random_with_brain(
+        rng: &mut dyn RngCore,
+        generation_length: usize,
+        animals: usize,
+        foods: usize,
+        brain: brain::BrainConfig,
     ) -> Self {
         Self {
-            world: world::World::random(rng, animals, foods),
+            world: world::World::random(rng, animals, foods, brain),
             genetic_algo: ga::GeneticAlgorithm::new(
                 ga::selection::RoulleteWheelSelection::new(),
                 ga::crossover::UniformCrossover::new(),
                 // chance and coefficient chosen with trial and error
                 // higher values cause more chaos
-                ga::mutation::GaussianMutation::new(0.01, 0.3)
+                ga::mutation::UniformMutation::new(0.01, 0.3)
             ),
             age: 0,
             generation_length,
+            generation: 0,
+            hall_of_fame: ga::hall_of_fame::HallOfFame::new(HALL_OF_FAME_SIZE),
+            brain,
         }
     }
 
+    // how many of the best animals make it into the next generation
+    // as they are; none by default
+    pub fn with_elites(mut self, elites: usize) -> Self {
+        self.genetic_algo = self.genetic_algo.with_elites(elites);
+        self
+    }
+
     pub fn world(&self) -> &world::World {
         &self.world
     }
 
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Best brains of all the generations evolved so far, with the fitness
+    // they had and the generation they lived in
+    pub fn hall_of_fame(&self) -> &ga::hall_of_fame::HallOfFame {
+        &self.hall_of_fame
+    }
+
+    // Neuron activations of the given animal for what it currently sees;
+    // doesn't advance the animal's memory
+    pub fn trace(&self, animal: usize) -> Option<nn::Trace> {
+        let animal = self.world.animals.get(animal)?;
+
+        let vision = animal.eye
+            .process_vision(
+                animal.position,
+                animal.rotation,
+                &self.world.food
+            );
+
+        Some(animal.brain.trace(vision))
+    }
+
+    // SVG diagram of the given animal's brain
+    pub fn diagram(&self, animal: usize) -> Option<String> {
+        let animal = self.world.animals.get(animal)?;
+
+        Some(animal.brain.diagram(&animal.eye))
+    }
+
+    // What every animal currently sees; record these over a few steps
+    // to get samples for `saliency`
+    pub fn visions(&self) -> Vec<Vec<f32>> {
+        self.world.animals
+            .iter()
+            .map(|animal| animal.eye
+                .process_vision(
+                    animal.position,
+                    animal.rotation,
+                    &self.world.food
+                ))
+            .collect()
+    }
+
+    // How much each of the given animal's outputs depends on each
+    // photoreceptor, `[output][photoreceptor]`
+    pub fn saliency(
+        &self,
+        animal: usize,
+        visions: &[Vec<f32>],
+        method: nn::Saliency
+    ) -> Option<Vec<Vec<f32>>> {
+        let animal = self.world.animals.get(animal)?;
+
+        Some(animal.brain.neural_network.saliency(visions, method))
+    }
+
+    pub fn diversity(&self) -> Diversity {
+        let animals = &self.world.animals;
+        let probes = self.visions();
+
+        let mut weights = 0.0;
+        let mut behaviour = 0.0;
+        let mut pairs = 0;
+
+        for (idx, a) in animals.iter().enumerate() {
+            for b in &animals[idx + 1..] {
+                let (a, b) = (&a.brain.neural_network, &b.brain.neural_network);
+
+                weights += a.distance(b, nn::Distance::L2);
+                behaviour += a.behavioural_distance(b, &probes, nn::Distance::L2);
+                pairs += 1;
+            }
+        }
+
+        if pairs == 0 {
+            return Diversity { weights: 0.0, behaviour: 0.0 };
+        }
+
+        Diversity {
+            weights: weights / pairs as f32,
+            behaviour: behaviour / pairs as f32,
+        }
+    }
+
     pub fn step(&mut self, rng: &mut dyn RngCore) {
         self.handle_collision(rng);
-        self.handle_decisions();
+        self.handle_decisions(rng);
         self.process_movement();
 
         self.age += 1;
@@ -92,7 +242,7 @@ impl Simulation {
         }
     }
 
-    fn handle_decisions(&mut self) {
+    fn handle_decisions(&mut self, rng: &mut dyn RngCore) {
         // for each animal
         for animal in &mut self.world.animals {
             // process vision
@@ -104,22 +254,33 @@ impl Simulation {
                 );
 
             // get "decisions" from brain
-            let decisions = animal.brain.neural_network
-                .propagate(vision);
+            let decision = animal.brain
+                .decide(vision, rng);
 
-            // decision #1: speed change
-            let delta_speed = decisions[0]
-                .clamp(
-                    -LIN_ACCELERATION,
-                    LIN_ACCELERATION
-                );
-
-            // decision #2: change in direction
-            let delta_theta = decisions[1]
-                .clamp(
-                    -ROT_ACCELERATION,
-                    ROT_ACCELERATION
-                );
+            let (delta_speed, delta_theta) = match decision {
+                nn::Decision::Continuous(decisions) => (
+                    // decision #1: speed change
+                    decisions[0]
+                        .clamp(
+                            -LIN_ACCELERATION,
+                            LIN_ACCELERATION
+                        ),
+                    // decision #2: change in direction
+                    decisions[1]
+                        .clamp(
+                            -ROT_ACCELERATION,
+                            ROT_ACCELERATION
+                        )
+                ),
+                // the expected outcome of all actions
+                nn::Decision::Probabilities(probabilities) => ACTIONS
+                    .iter()
+                    .zip(probabilities)
+                    .fold((0.0, 0.0), |(speed, theta), ((delta_speed, delta_theta), p)| {
+                        (speed + p * delta_speed, theta + p * delta_theta)
+                    }),
+                nn::Decision::Action(action) => ACTIONS[action],
+            };
 
             // Apply the changes made from "decisions"
             animal.speed = (animal.speed + delta_speed)
@@ -143,6 +304,9 @@ impl Simulation {
             .map(individual::AnimalIndividual::from_animal)
             .collect();
 
+        self.hall_of_fame
+            .record(&current_population, self.generation);
+
         // Evolve the animals
         let evolved_population = self.genetic_algo
             .iterate(rng, &current_population);
@@ -150,12 +314,14 @@ impl Simulation {
         // Prepare the evolved population for the simulation
         self.world.animals = evolved_population
             .into_iter()
-            .map(|individual| individual.as_animal(rng))
+            .map(|individual| individual.as_animal(self.brain, rng))
             .collect();
 
         // Prepare the food
         for food in &mut self.world.food {
             food.position = rng.gen();
         }
+
+        self.generation += 1;
     }
 }
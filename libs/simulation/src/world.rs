@@ -31,10 +31,10 @@ impl Animal {
         }
     }
 
-    pub fn random(rng: &mut dyn RngCore) -> Self {
+    pub fn random(rng: &mut dyn RngCore, config: brain::BrainConfig) -> Self {
         let eye = eye::Eye::default();
 
-        let brain = brain::Brain::randomize(rng, &eye);
+        let brain = brain::Brain::randomize(rng, &eye, config);
 
         Self {
             brain,
@@ -49,10 +49,12 @@ impl Animal {
 
     pub fn from_chromosome(
         chromosome: ga::individual::Chromosome,
+        config: brain::BrainConfig,
         rng: &mut dyn RngCore
     ) -> Self {
         let eye = eye::Eye::default();
-        let brain = brain::Brain::from_chromosome(chromosome, &eye);
+        let brain = brain::Brain::from_chromosome(chromosome, &eye, config)
+            .unwrap_or_else(|err| panic!("{}", err));
 
         Self::new(brain, eye, rng)
     }
@@ -98,9 +100,14 @@ pub struct World {
 
 impl World {
     //TODO: prevent entities in world from overlapping
-    pub fn random(rng: &mut dyn RngCore, animals: usize, foods: usize) -> Self {
+    pub fn random(
+        rng: &mut dyn RngCore,
+        animals: usize,
+        foods: usize,
+        config: brain::BrainConfig
+    ) -> Self {
         let animals = (0..animals)
-            .map(|_| Animal::random(rng))
+            .map(|_| Animal::random(rng, config))
             .collect();
         
         let food = (0..foods)